@@ -4,12 +4,8 @@ function judge(outputFile) {
     try {
         let jsonResult = JSON.parse(outputFile);
         let points = {};
-        jsonResult.exercises.forEach(({ name, result }) => {
-            if (result) {
-                points[name] = [1,1]
-            } else {
-                points[name] = [0,1]
-            }
+        jsonResult.exercises.forEach(({ name, points: earned, max_points }) => {
+            points[name] = [earned, max_points]
         })
         return points;
     } catch(e) {
@@ -0,0 +1,84 @@
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Bundle info.toml, the unsolved exercises under curriculum/exercises/ and
+// the tracks into the binary, so that `rustlings init` can create a fresh set
+// of exercises anywhere. The exercises/ of the working tree may be solved, so
+// it's never embedded. The earlier versions of the exercises under
+// curriculum/history/, if any, are bundled too, by their hash, so
+// `rustlings update` can merge with the version a student started from.
+fn main() {
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let curriculum = root.join("curriculum");
+    println!("cargo:rerun-if-changed=info.toml");
+    // Cargo looks for changes anywhere inside a directory
+    println!("cargo:rerun-if-changed=curriculum");
+    println!("cargo:rerun-if-changed=tracks");
+
+    let mut files = Vec::new();
+    collect(&curriculum, &curriculum.join("exercises"), &mut files);
+    if root.join("tracks").is_dir() {
+        collect(&root, &root.join("tracks"), &mut files);
+    }
+    files.sort();
+
+    let mut embedded = format!(
+        "pub static INFO_TOML: &str = include_str!({:?});\n\
+         pub static FILES: &[(&str, &[u8])] = &[\n",
+        root.join("info.toml").display().to_string()
+    );
+    for (relative, path) in &files {
+        embedded.push_str(&format!(
+            "    ({relative:?}, include_bytes!({:?})),\n",
+            path.display().to_string()
+        ));
+    }
+    embedded.push_str("];\n");
+
+    // Every version is bundled once, even if several earlier ones had it
+    let mut prior = BTreeMap::new();
+    for version in fs::read_dir(curriculum.join("history")).into_iter().flatten() {
+        let version = version.unwrap().path();
+        let mut files = Vec::new();
+        collect(&version, &version.join("exercises"), &mut files);
+        for (relative, path) in files {
+            let hash: String = Sha256::digest(fs::read(&path).unwrap())
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect();
+            prior.insert(hash, (relative, path));
+        }
+    }
+    embedded.push_str("pub static PRIOR_ORIGINALS: &[(&str, &str, &[u8])] = &[\n");
+    for (hash, (relative, path)) in &prior {
+        embedded.push_str(&format!(
+            "    ({hash:?}, {relative:?}, include_bytes!({:?})),\n",
+            path.display().to_string()
+        ));
+    }
+    embedded.push_str("];\n");
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("embedded.rs");
+    fs::write(out, embedded).unwrap();
+}
+
+// Every file in the directory, along with its path relative to the root
+// using forward slashes
+fn collect(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect(root, &path, files);
+        } else {
+            let relative: Vec<String> = path
+                .strip_prefix(root)
+                .unwrap()
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            files.push((relative.join("/"), path));
+        }
+    }
+}
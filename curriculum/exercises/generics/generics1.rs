@@ -0,0 +1,14 @@
+// generics1.rs
+//
+// This shopping list program isn't compiling! Use your knowledge of generics to
+// fix it.
+//
+// Execute `rustlings hint generics1` or use the `hint` watch subcommand for a
+// hint.
+
+// I AM NOT DONE
+
+fn main() {
+    let mut shopping_list: Vec<?> = Vec::new();
+    shopping_list.push("milk");
+}
@@ -0,0 +1,17 @@
+// strings1.rs
+//
+// Make me compile without changing the function signature!
+//
+// Execute `rustlings hint strings1` or use the `hint` watch subcommand for a
+// hint.
+
+// I AM NOT DONE
+
+fn main() {
+    let answer = current_favorite_color();
+    println!("My current favorite color is {}", answer);
+}
+
+fn current_favorite_color() -> String {
+    "blue"
+}
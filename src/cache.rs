@@ -0,0 +1,220 @@
+use crate::diagnostics::Diagnostic;
+use crate::exercise::{absolute, Exceeded, Exercise, ExerciseOutput, RunOptions};
+use crate::progress::hash;
+use crate::workspace::bin_name;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+// Where the verdicts are kept unless told otherwise, one file for every exercise
+pub const CACHE_DIR: &str = "target/rustlings-cache";
+
+// How far the exercise got the last time it was checked
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    // It didn't compile
+    CompileFailed,
+    // It compiled, but wasn't run yet
+    Compiled,
+    // It compiled and ran successfully
+    Passed,
+}
+
+// The result of compiling and running the exercise the last time
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Verdict {
+    // The hash of everything the result depends on
+    key: String,
+    pub stage: Stage,
+    stdout: String,
+    stderr: String,
+    exceeded: Option<Exceeded>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Verdict {
+    pub fn output(&self) -> ExerciseOutput {
+        ExerciseOutput {
+            stdout: self.stdout.clone(),
+            stderr: self.stderr.clone(),
+            exceeded: self.exceeded,
+            diagnostics: self.diagnostics.clone(),
+        }
+    }
+
+    // The output of running the exercise, if it passed
+    pub fn passed(&self) -> Option<ExerciseOutput> {
+        (self.stage == Stage::Passed).then(|| self.output())
+    }
+}
+
+// Where the verdict of an exercise is cached, along with the key it's valid for
+pub struct CacheEntry {
+    path: PathBuf,
+    key: String,
+}
+
+impl CacheEntry {
+    // The entry for the exercise as it is right now, compiled along with the
+    // hidden tests if given. None if the exercise can't be read or nothing
+    // is cached.
+    pub fn new(exercise: &Exercise, hidden_tests: Option<&Path>, options: &RunOptions) -> Option<Self> {
+        let dir = options.cache.as_ref()?;
+        let mut inputs = format!(
+            "{}\n{:?}\n{:?}\n{:?}\n{:?}\n{:?}\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            exercise.mode,
+            options.backend,
+            exercise.dependencies,
+            exercise.timeout(&options.limits),
+            options.limits,
+            rustc_version(),
+        );
+        inputs.push_str(&fs::read_to_string(&exercise.path).ok()?);
+        if let Some(hidden_tests) = hidden_tests {
+            inputs.push_str(&fs::read_to_string(hidden_tests).ok()?);
+        }
+        for watched in &exercise.watch {
+            add_contents(&mut inputs, watched);
+        }
+        // Solutions and exercises with hidden tests get entries of their own
+        let mut name = bin_name(&absolute(&exercise.path));
+        if hidden_tests.is_some() {
+            name.push_str("-hidden");
+        }
+        Some(CacheEntry {
+            path: dir.join(format!("{name}.json")),
+            key: hash(&inputs),
+        })
+    }
+
+    // The cached verdict, unless anything changed since
+    pub fn load(&self) -> Option<Verdict> {
+        let verdict: Verdict = serde_json::from_str(&fs::read_to_string(&self.path).ok()?).ok()?;
+        (verdict.key == self.key).then_some(verdict)
+    }
+
+    // Cache the verdict. Only the stages that come out the same every time are
+    // cached, so nothing that exceeded a limit or was cancelled, as it might
+    // not the next time on a less busy machine.
+    pub fn store(&self, stage: Stage, output: &ExerciseOutput) {
+        if output.exceeded.is_some() {
+            return;
+        }
+        let verdict = Verdict {
+            key: self.key.clone(),
+            stage,
+            stdout: output.stdout.clone(),
+            stderr: output.stderr.clone(),
+            exceeded: output.exceeded,
+            diagnostics: output.diagnostics.clone(),
+        };
+        // Failing to cache only makes the next check slower
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        let _ignored = fs::create_dir_all(dir)
+            .and_then(|_| fs::write(&self.path, serde_json::to_string(&verdict).unwrap_or_default()));
+    }
+}
+
+// The version of the compiler, including the commit it was built from
+fn rustc_version() -> &'static str {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION.get_or_init(|| {
+        Command::new("rustc")
+            .arg("-vV")
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .unwrap_or_default()
+    })
+}
+
+// Add the contents of the file, or of all files in the directory
+fn add_contents(inputs: &mut String, path: &Path) {
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = fs::read_dir(path)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .collect();
+        entries.sort();
+        for entry in entries {
+            add_contents(inputs, &entry);
+        }
+    } else if let Ok(contents) = fs::read(path) {
+        let _ = writeln!(inputs, "{}\n{}", path.display(), hash(&String::from_utf8_lossy(&contents)));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use std::env;
+    use std::process;
+    use std::time::Duration;
+
+    fn exercise(mode: Mode) -> Exercise {
+        Exercise::for_test("cached", "tests/fixture/success/testSuccess.rs", mode)
+    }
+
+    // Caching in a directory of the test's own, so that it doesn't leave
+    // verdicts in the real cache
+    fn options(name: &str) -> RunOptions {
+        RunOptions {
+            cache: Some(env::temp_dir().join(format!("rustlings_cache_{name}_{}", process::id()))),
+            ..RunOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let options = options("key");
+        let test = CacheEntry::new(&exercise(Mode::Test), None, &options).unwrap();
+        assert_eq!(test.key, CacheEntry::new(&exercise(Mode::Test), None, &options).unwrap().key);
+        assert_ne!(test.key, CacheEntry::new(&exercise(Mode::Compile), None, &options).unwrap().key);
+        let hidden = Path::new("tests/fixture/success/compSuccess.rs");
+        let with_hidden = CacheEntry::new(&exercise(Mode::Test), Some(hidden), &options).unwrap();
+        assert_ne!(test.key, with_hidden.key);
+        assert_ne!(test.path, with_hidden.path);
+        let mut longer = exercise(Mode::Test);
+        longer.timeout = Some(60);
+        assert_ne!(test.key, CacheEntry::new(&longer, None, &options).unwrap().key);
+        assert!(CacheEntry::new(&exercise(Mode::Test), None, &RunOptions::default()).is_none());
+    }
+
+    #[test]
+    fn test_store_and_load() {
+        let options = options("store");
+        let mut failing = exercise(Mode::Compile);
+        failing.path = PathBuf::from("tests/fixture/failure/compFailure.rs");
+        let entry = CacheEntry::new(&failing, None, &options).unwrap();
+        let output = ExerciseOutput {
+            stderr: String::from("expected `;`"),
+            ..ExerciseOutput::default()
+        };
+        entry.store(Stage::CompileFailed, &output);
+        let verdict = entry.load().unwrap();
+        assert_eq!(verdict.stage, Stage::CompileFailed);
+        assert_eq!(verdict.output().stderr, "expected `;`");
+        assert!(verdict.passed().is_none());
+
+        // Exceeding a limit or being cancelled doesn't replace the last verdict
+        for exceeded in [Exceeded::Cancelled, Exceeded::Timeout(Duration::from_secs(1))] {
+            let output = ExerciseOutput {
+                exceeded: Some(exceeded),
+                ..ExerciseOutput::default()
+            };
+            entry.store(Stage::Compiled, &output);
+            assert_eq!(entry.load().unwrap().stage, Stage::CompileFailed);
+        }
+
+        entry.store(Stage::Passed, &ExerciseOutput::default());
+        assert!(entry.load().unwrap().passed().is_some());
+        fs::remove_dir_all(options.cache.unwrap()).unwrap();
+    }
+}
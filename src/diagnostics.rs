@@ -0,0 +1,342 @@
+use console::Style;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+// A message of the compiler, as written by `--error-format=json`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Diagnostic {
+    pub message: String,
+    pub code: Option<DiagnosticCode>,
+    // Like `error`, `warning`, `note` or `help`
+    pub level: String,
+    pub spans: Vec<DiagnosticSpan>,
+    // Notes and help attached to the message
+    pub children: Vec<Diagnostic>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiagnosticCode {
+    // Like `E0382` or `clippy::float_cmp`
+    pub code: String,
+    // The compiler's explanation of the error code, if it has one
+    pub explanation: Option<String>,
+}
+
+// The source code a message is about
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiagnosticSpan {
+    pub file_name: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+    // Whether this is where the problem is, rather than related code
+    pub is_primary: bool,
+    // The lines of the span
+    pub text: Vec<DiagnosticText>,
+    pub label: Option<String>,
+    // The code suggested to replace the span with
+    pub suggested_replacement: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DiagnosticText {
+    pub text: String,
+    // The columns of the highlighted part, starting at 1
+    pub highlight_start: usize,
+    pub highlight_end: usize,
+}
+
+// What cargo writes for every message with `--message-format=json`
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<Diagnostic>,
+    // The binary of a compiled artifact, if it is one
+    executable: Option<PathBuf>,
+    // The environment variables a build script set with `cargo:rustc-env`
+    #[serde(default)]
+    env: Vec<(String, String)>,
+}
+
+// A binary cargo compiled
+#[derive(Debug, PartialEq)]
+pub struct Executable {
+    pub path: PathBuf,
+    // The environment variables its build script set, which cargo would
+    // also set when running it
+    pub env: Vec<(String, String)>,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.level == "error"
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.level == "warning"
+    }
+
+    // The code of the error, like `E0382`
+    pub fn error_code(&self) -> Option<&str> {
+        self.code.as_ref().map(|code| code.code.as_str())
+    }
+
+    // The compiler's closing words, like `aborting due to 2 previous errors`,
+    // which are replaced with our own
+    fn is_summary(&self) -> bool {
+        self.level == "failure-note"
+            || (self.spans.is_empty()
+                && (self.message.starts_with("aborting due to") || self.message.ends_with("emitted")))
+    }
+
+    // Point the message at another file, like the exercise instead of its copy
+    pub fn rename_file(&mut self, from: &str, to: &str) {
+        self.rename_files(&|file| (file == from).then(|| to.to_string()));
+    }
+
+    // Make the paths of the files relative to the directory, cargo reports absolute paths
+    pub fn relative_to(&mut self, dir: &Path) {
+        self.rename_files(&|file| {
+            let relative = Path::new(file).strip_prefix(dir).ok()?;
+            Some(relative.display().to_string())
+        });
+    }
+
+    fn rename_files(&mut self, rename: &impl Fn(&str) -> Option<String>) {
+        for span in &mut self.spans {
+            if let Some(renamed) = rename(&span.file_name) {
+                span.file_name = renamed;
+            }
+        }
+        for child in &mut self.children {
+            child.rename_files(rename);
+        }
+    }
+
+    // Render the message like the compiler would, colored for terminals
+    // if `color` is set
+    pub fn render(&self, color: bool) -> String {
+        let paint = |style: Style, text: &str| {
+            if color {
+                style.apply_to(text).to_string()
+            } else {
+                text.to_string()
+            }
+        };
+        let level_style = match self.level.as_str() {
+            "error" => Style::new().red().bold(),
+            "warning" => Style::new().yellow().bold(),
+            _ => Style::new().cyan().bold(),
+        };
+        let gutter_style = Style::new().blue().bold();
+        let width = self
+            .spans
+            .iter()
+            .chain(self.children.iter().flat_map(|child| &child.spans))
+            .map(|span| span.line_start.to_string().len())
+            .max()
+            .unwrap_or(0);
+        let pad = " ".repeat(width);
+        let gutter = |line: &str| paint(gutter_style.clone(), &format!("{line:>width$} |"));
+
+        let mut rendered = paint(level_style.clone(), &self.level);
+        if let Some(code) = self.error_code() {
+            rendered += &paint(level_style.clone(), &format!("[{code}]"));
+        }
+        rendered += &paint(Style::new().bold(), &format!(": {}", self.message));
+        rendered.push('\n');
+
+        let mut spans: Vec<&DiagnosticSpan> = self.spans.iter().collect();
+        spans.sort_by_key(|span| (span.line_start, span.column_start));
+        if let Some(primary) = spans.iter().find(|span| span.is_primary) {
+            let arrow = paint(gutter_style.clone(), "-->");
+            let _ = writeln!(
+                rendered,
+                "{pad}{arrow} {}:{}:{}",
+                primary.file_name, primary.line_start, primary.column_start
+            );
+            let _ = writeln!(rendered, "{}", gutter(""));
+        }
+        let mut last_line = None;
+        for span in &spans {
+            let text = match span.text.first() {
+                Some(text) => text,
+                None => continue,
+            };
+            if last_line != Some(span.line_start) {
+                let _ = writeln!(rendered, "{} {}", gutter(&span.line_start.to_string()), text.text);
+                last_line = Some(span.line_start);
+            }
+            let (marker, marker_style) = if span.is_primary {
+                ("^", level_style.clone())
+            } else {
+                ("-", gutter_style.clone())
+            };
+            let markers = marker.repeat(text.highlight_end.saturating_sub(text.highlight_start).max(1));
+            let label = span.label.as_deref().unwrap_or_default();
+            let marked = format!("{markers} {label}");
+            let indent = " ".repeat(text.highlight_start.saturating_sub(1));
+            let _ = writeln!(rendered, "{} {indent}{}", gutter(""), paint(marker_style, marked.trim_end()));
+        }
+
+        for child in &self.children {
+            let level = paint(Style::new().bold(), &child.level);
+            let _ = writeln!(rendered, "{pad} = {level}: {}", child.message);
+            // Show suggested code the way it would look with the suggestion applied
+            for span in &child.spans {
+                let (text, replacement) = match (span.text.first(), &span.suggested_replacement) {
+                    (Some(text), Some(replacement)) if span.line_start == span.line_end => (text, replacement),
+                    _ => continue,
+                };
+                let line: Vec<char> = text.text.chars().collect();
+                let start = span.column_start.saturating_sub(1).min(line.len());
+                let end = span.column_end.saturating_sub(1).clamp(start, line.len());
+                let before: String = line[..start].iter().collect();
+                let after: String = line[end..].iter().collect();
+                let suggested = paint(Style::new().green(), replacement);
+                let _ = writeln!(rendered, "{} {before}{suggested}{after}", gutter(&span.line_start.to_string()));
+            }
+        }
+        rendered
+    }
+}
+
+// Take the messages of the compiler out of its output, returning them along
+// with the rest of the output. The output may mix JSON messages of rustc or
+// cargo with plain text, like the output of tests run by cargo.
+pub fn extract(output: &str) -> (Vec<Diagnostic>, String) {
+    let mut diagnostics = Vec::new();
+    let mut rest = String::new();
+    for line in output.lines() {
+        if !line.starts_with('{') {
+            rest.push_str(line);
+            rest.push('\n');
+            continue;
+        }
+        let diagnostic = match serde_json::from_str::<CargoMessage>(line) {
+            Ok(message) if message.reason == "compiler-message" => message.message,
+            // Messages about artifacts and build scripts aren't interesting
+            Ok(_) => None,
+            Err(_) => match serde_json::from_str::<Diagnostic>(line) {
+                Ok(diagnostic) => Some(diagnostic),
+                Err(_) => {
+                    rest.push_str(line);
+                    rest.push('\n');
+                    None
+                }
+            },
+        };
+        diagnostics.extend(diagnostic.filter(|diagnostic| !diagnostic.is_summary()));
+    }
+    (diagnostics, rest)
+}
+
+// The binary cargo compiled, from the artifact it reports last
+pub fn executable(output: &str) -> Option<Executable> {
+    let messages: Vec<CargoMessage> = output
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    let path = messages
+        .iter()
+        .filter(|message| message.reason == "compiler-artifact")
+        .filter_map(|message| message.executable.clone())
+        .next_back()?;
+    let env = messages
+        .into_iter()
+        .filter(|message| message.reason == "build-script-executed")
+        .flat_map(|message| message.env)
+        .collect();
+    Some(Executable { path, env })
+}
+
+// How many errors and warnings there are, like `2 errors, 1 warning`
+pub fn counts(diagnostics: &[Diagnostic]) -> Option<String> {
+    let plural = |count: usize, what: &str| match count {
+        1 => format!("1 {what}"),
+        _ => format!("{count} {what}s"),
+    };
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warnings = diagnostics.iter().filter(|d| d.is_warning()).count();
+    match (errors, warnings) {
+        (0, 0) => None,
+        (0, _) => Some(plural(warnings, "warning")),
+        (_, 0) => Some(plural(errors, "error")),
+        _ => Some(format!("{}, {}", plural(errors, "error"), plural(warnings, "warning"))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MOVED: &str = r#"{"$message_type":"diagnostic","message":"borrow of moved value: `v`","code":{"code":"E0382","explanation":"A variable was used after its contents have been moved elsewhere.\n"},"level":"error","spans":[{"file_name":"m1.rs","byte_start":45,"byte_end":46,"line_start":3,"line_end":3,"column_start":13,"column_end":14,"is_primary":false,"text":[{"text":"    let w = v;","highlight_start":13,"highlight_end":14}],"label":"value moved here","suggested_replacement":null,"suggestion_applicability":null,"expansion":null},{"file_name":"m1.rs","byte_start":74,"byte_end":75,"line_start":4,"line_end":4,"column_start":27,"column_end":28,"is_primary":true,"text":[{"text":"    println!(\"{:?}\", v);","highlight_start":27,"highlight_end":28}],"label":"value borrowed here after move","suggested_replacement":null,"suggestion_applicability":null,"expansion":null}],"children":[{"message":"consider cloning the value if the performance cost is acceptable","code":null,"level":"help","spans":[{"file_name":"m1.rs","byte_start":46,"byte_end":46,"line_start":3,"line_end":3,"column_start":14,"column_end":14,"is_primary":true,"text":[{"text":"    let w = v;","highlight_start":14,"highlight_end":14}],"label":null,"suggested_replacement":".clone()","suggestion_applicability":"MachineApplicable","expansion":null}],"children":[],"rendered":null}],"rendered":"error[E0382]: borrow of moved value: `v`\n"}"#;
+
+    #[test]
+    fn test_extract_rustc_messages() {
+        let aborting = r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n\n"}"#;
+        let output = format!("{MOVED}\n{aborting}\nnot json\n");
+        let (diagnostics, rest) = extract(&output);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].error_code(), Some("E0382"));
+        assert_eq!(rest, "not json\n");
+        assert_eq!(counts(&diagnostics).as_deref(), Some("1 error"));
+    }
+
+    #[test]
+    fn test_extract_cargo_messages() {
+        let output = format!(
+            "{{\"reason\":\"compiler-artifact\",\"package_id\":\"x\"}}\n\
+            {{\"reason\":\"compiler-message\",\"package_id\":\"x\",\"message\":{MOVED}}}\n\
+            test result: FAILED. 0 passed; 1 failed\n"
+        );
+        let (diagnostics, rest) = extract(&output);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(rest, "test result: FAILED. 0 passed; 1 failed\n");
+    }
+
+    #[test]
+    fn test_executable() {
+        let output = "{\"reason\":\"compiler-artifact\",\"package_id\":\"rand\",\"executable\":null}\n\
+            {\"reason\":\"compiler-artifact\",\"package_id\":\"x\",\"executable\":\"/w/target/debug/intro1\"}\n\
+            {\"reason\":\"build-script-executed\",\"package_id\":\"x\",\"env\":[[\"TEST_FOO\",\"1\"]]}\n\
+            {\"reason\":\"build-finished\",\"success\":true}\n";
+        assert_eq!(
+            executable(output),
+            Some(Executable {
+                path: PathBuf::from("/w/target/debug/intro1"),
+                env: vec![("TEST_FOO".to_string(), "1".to_string())],
+            })
+        );
+        assert_eq!(executable("not json\n"), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let (diagnostics, _) = extract(MOVED);
+        let expected = "error[E0382]: borrow of moved value: `v`\n \
+            --> m1.rs:4:27\n  \
+            |\n\
+            3 |     let w = v;\n  \
+            |             - value moved here\n\
+            4 |     println!(\"{:?}\", v);\n  \
+            |                           ^ value borrowed here after move\n  \
+            = help: consider cloning the value if the performance cost is acceptable\n\
+            3 |     let w = v.clone();\n";
+        assert_eq!(diagnostics[0].render(false), expected);
+    }
+
+    #[test]
+    fn test_counts() {
+        let (mut diagnostics, _) = extract(MOVED);
+        assert_eq!(counts(&[]), None);
+        let mut warning = diagnostics[0].clone();
+        warning.level = "warning".to_string();
+        diagnostics.push(warning.clone());
+        diagnostics.push(warning);
+        assert_eq!(counts(&diagnostics).as_deref(), Some("1 error, 2 warnings"));
+    }
+}
@@ -0,0 +1,175 @@
+use crate::exercise::Exercise;
+use crate::pristine::PRISTINE_DIR;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// The info.toml, the unsolved files under curriculum/exercises/ and the
+// tracks the binary was built with, and the earlier versions of the exercises
+// by their hash, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+// The original version of the file at the path relative to info.toml
+pub fn file(path: &Path) -> Option<&'static [u8]> {
+    let path = slashed(path);
+    FILES
+        .iter()
+        .find(|(embedded, _)| *embedded == path)
+        .map(|(_, contents)| *contents)
+}
+
+// The versions the exercise at the path relative to info.toml was shipped
+// with before the current one, along with their hashes
+pub fn prior_originals(path: &Path) -> impl Iterator<Item = (&'static str, String)> {
+    let path = slashed(path);
+    PRIOR_ORIGINALS
+        .iter()
+        .filter(move |(_, embedded, _)| *embedded == path)
+        .filter_map(|(hash, _, contents)| Some((*hash, String::from_utf8(contents.to_vec()).ok()?)))
+}
+
+fn slashed(path: &Path) -> String {
+    let path: Vec<String> = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    path.join("/")
+}
+
+// The original version of the exercise, if it's one the binary was built with
+pub fn exercise(exercise: &Exercise) -> Option<String> {
+    let contents = file(&exercise.path)?;
+    String::from_utf8(contents.to_vec()).ok()
+}
+
+// Create a fresh set of exercises in the directory, which must not have any yet.
+// Their original versions are kept too, to reset and update them later.
+pub fn init(dir: &Path) -> io::Result<()> {
+    let info = dir.join("info.toml");
+    if info.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", info.display()),
+        ));
+    }
+    for (path, contents) in FILES {
+        let mut copies = vec![dir.join(path)];
+        if path.starts_with("exercises/") {
+            copies.push(dir.join(PRISTINE_DIR).join(path));
+        }
+        for path in copies {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+    }
+    fs::write(info, INFO_TOML)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::I_AM_DONE_REGEX;
+    use crate::integrity::tests_fingerprint;
+    use crate::track;
+    use regex::Regex;
+
+    // The exercises of info.toml and the tracks it includes
+    fn exercises() -> Vec<Exercise> {
+        let read = |path: &Path| {
+            if path == Path::new("info.toml") {
+                return Some(INFO_TOML.to_string());
+            }
+            String::from_utf8(file(path)?.to_vec()).ok()
+        };
+        track::combine(track::load_with(Path::new("info.toml"), &read).unwrap()).exercises
+    }
+
+    #[test]
+    fn test_every_exercise_is_embedded() {
+        for exercise in &exercises() {
+            assert!(file(&exercise.path).is_some(), "{} isn't embedded", exercise.path.display());
+        }
+        assert!(file(Path::new("exercises/README.md")).is_some());
+        assert!(file(Path::new("tracks/algorithms.toml")).is_some());
+        assert!(file(Path::new("exercises/missing.rs")).is_none());
+    }
+
+    #[test]
+    fn test_embedded_exercises_are_not_done() {
+        let marker = Regex::new(I_AM_DONE_REGEX).unwrap();
+        for exercise in &exercises() {
+            let source = self::exercise(exercise).unwrap();
+            assert!(marker.is_match(&source), "{} isn't marked as not done", exercise.path.display());
+            if let Some(fingerprint) = &exercise.tests_fingerprint {
+                assert_eq!(
+                    tests_fingerprint(&source),
+                    *fingerprint,
+                    "the tests of {} were changed",
+                    exercise.path.display()
+                );
+            }
+        }
+    }
+
+    // Every file under the directory, by its path relative to the current one
+    fn files(dir: &Path, files: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                self::files(&path, files);
+            } else {
+                files.push(slashed(&path));
+            }
+        }
+    }
+
+    // exercises/ may be solved, but it mustn't drift apart from the copies
+    // that are handed out otherwise: both have the same files, the same tests
+    // where info.toml fixes them, and only the Rust files, which may be
+    // solved, differ
+    #[test]
+    fn test_exercises_match_the_curriculum() {
+        let mut working = Vec::new();
+        files(Path::new("exercises"), &mut working);
+        working.sort();
+        let embedded: Vec<&str> = FILES
+            .iter()
+            .map(|(path, _)| *path)
+            .filter(|path| path.starts_with("exercises/"))
+            .collect();
+        assert_eq!(working, embedded, "exercises/ and curriculum/exercises/ don't have the same files");
+
+        let exercises = exercises();
+        let drifted: Vec<&str> = FILES
+            .iter()
+            .filter(|(path, _)| path.starts_with("exercises/"))
+            .filter(|(path, contents)| {
+                let yours = fs::read(path).unwrap();
+                if !path.ends_with(".rs") {
+                    return yours != *contents;
+                }
+                let fingerprint = exercises
+                    .iter()
+                    .find(|exercise| slashed(&exercise.path) == *path)
+                    .and_then(|exercise| exercise.tests_fingerprint.as_ref());
+                fingerprint.is_some_and(|fingerprint| {
+                    tests_fingerprint(&String::from_utf8_lossy(&yours)) != *fingerprint
+                        || tests_fingerprint(&String::from_utf8_lossy(contents)) != *fingerprint
+                })
+            })
+            .map(|(path, _)| *path)
+            .collect();
+        assert!(drifted.is_empty(), "these files differ from their copies under curriculum/: {drifted:#?}");
+    }
+
+    #[test]
+    fn test_prior_originals_are_not_done() {
+        let marker = Regex::new(I_AM_DONE_REGEX).unwrap();
+        for (_, path, contents) in PRIOR_ORIGINALS {
+            let source = String::from_utf8_lossy(contents);
+            assert!(marker.is_match(&source), "the earlier version of {path} isn't marked as not done");
+        }
+    }
+}
@@ -1,32 +1,43 @@
+use crate::cache::{CacheEntry, Stage, Verdict};
+use crate::diagnostics::{counts, executable, extract, Diagnostic, Executable};
+use crate::workspace::{self, WORKSPACE_DIR};
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::fmt::{self, Display, Formatter};
-use std::fs::{self, remove_file, File};
-use std::io::Read;
-use std::path::PathBuf;
-use std::process::{self, Command};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::iter;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
-const RUSTC_COLOR_ARGS: &[&str] = &["--color", "always"];
+// The messages of the compiler are rendered by us, see `diagnostics.rs`
+const RUSTC_JSON_ARGS: &[&str] = &["--error-format", "json"];
+const CARGO_JSON_ARGS: &[&str] = &["--message-format", "json", "--color", "never"];
 const RUSTC_EDITION_ARGS: &[&str] = &["--edition", "2021"];
-const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
+pub const I_AM_DONE_REGEX: &str = r"(?m)^\s*///?\s*I\s+AM\s+NOT\s+DONE";
 const CONTEXT: usize = 2;
-const CLIPPY_CARGO_TOML_PATH: &str = "./exercises/clippy/Cargo.toml";
-const BUILD_SCRIPT_CARGO_TOML_PATH: &str = "./exercises/tests/Cargo.toml";
+// How often a running exercise is checked for having finished or exceeded its limits
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
 
-// Get a temporary file name that is hopefully unique
-#[inline]
-fn temp_file() -> String {
-    let thread_id: String = format!("{:?}", std::thread::current().id())
-        .chars()
-        .filter(|c| c.is_alphanumeric())
-        .collect();
+// The wall-clock time an exercise may run for, unless told otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+// The number of bytes an exercise may write to stdout or stderr by default
+pub const DEFAULT_OUTPUT_LIMIT: usize = 1024 * 1024;
 
-    format!("./temp_{}_{thread_id}", process::id())
-}
+// The module the hidden tests are compiled into, which prefixes the names of their tests
+pub const HIDDEN_TESTS_MODULE: &str = "hidden_tests";
+
+// Number of scratch directories created by this process so far,
+// used to keep their names unique
+static SCRATCH_DIRS: AtomicUsize = AtomicUsize::new(0);
 
 // The mode of the exercise.
-#[derive(Deserialize, Copy, Clone, Debug)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Mode {
     // Indicates that the exercise should be compiled as a binary
@@ -39,14 +50,46 @@ pub enum Mode {
     BuildScript,
 }
 
+// How the exercises are compiled
+#[derive(Deserialize, Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    // Every exercise is compiled on its own by `rustc`
+    #[default]
+    Rustc,
+    // The exercises are binary targets of a cargo workspace, which keeps
+    // its build artifacts between runs and lets exercises use crates
+    Cargo,
+}
+
 #[derive(Deserialize)]
 pub struct ExerciseList {
+    // A track may only include others
+    #[serde(default)]
     pub exercises: Vec<Exercise>,
+    // The requirements for passing the categories when grading
+    #[serde(default)]
+    pub categories: Vec<CategoryRubric>,
+    // How the exercises are compiled, `rustc` unless set
+    #[serde(default)]
+    pub backend: Backend,
+    // The tracks whose exercises come before these ones
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+// The requirements for passing a category of exercises when grading
+#[derive(Deserialize, Debug)]
+pub struct CategoryRubric {
+    // The name of the category
+    pub name: String,
+    // The number of exercises that need to pass
+    pub required: usize,
 }
 
 // A representation of a rustlings exercise.
 // This is deserialized from the accompanying info.toml file
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Clone, Debug)]
 pub struct Exercise {
     // Name of the exercise
     pub name: String,
@@ -56,6 +99,96 @@ pub struct Exercise {
     pub mode: Mode,
     // The hint text associated with the exercise
     pub hint: String,
+    // The number of seconds the exercise may run for, overriding the default timeout
+    #[serde(default)]
+    pub timeout: Option<u64>,
+    // The number of points the exercise is worth when grading
+    #[serde(default = "default_points")]
+    pub points: u32,
+    // The category the exercise is graded in, instead of the directory it lives in
+    #[serde(default)]
+    pub category: Option<String>,
+    // The fingerprint of the exercise's tests, used to detect tampering when grading
+    #[serde(default)]
+    pub tests_fingerprint: Option<String>,
+    // The path to extra tests that are only compiled in when grading
+    #[serde(default)]
+    pub hidden_tests: Option<PathBuf>,
+    // Other files or directories the exercise depends on, like its build script
+    #[serde(default)]
+    pub watch: Vec<PathBuf>,
+    // The names the exercise had before, to carry over progress when it's renamed
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    // The crates the exercise uses, written like the dependencies in a Cargo.toml
+    #[serde(default)]
+    pub dependencies: toml::value::Table,
+}
+
+fn default_points() -> u32 {
+    1
+}
+
+// The limits applied when running a compiled exercise
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    // The wall-clock time the exercise may run for, unless it sets its own timeout
+    pub timeout: Duration,
+    // The maximum size of the process' address space in bytes, only enforced on Unix
+    pub memory: Option<u64>,
+    // The maximum number of bytes the exercise may write to stdout or stderr
+    pub output: Option<usize>,
+}
+
+// Compilers may take as long as they need
+const COMPILER_LIMITS: Limits = Limits {
+    timeout: Duration::MAX,
+    memory: None,
+    output: None,
+};
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            timeout: DEFAULT_TIMEOUT,
+            memory: None,
+            output: Some(DEFAULT_OUTPUT_LIMIT),
+        }
+    }
+}
+
+// Tells the compilations and runs of exercises to stop early.
+// Clones share the same flag, so it can be set from another thread.
+#[derive(Clone, Default, Debug)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    // Clear the flag, returning whether it was set
+    pub fn reset(&self) -> bool {
+        self.0.swap(false, Ordering::SeqCst)
+    }
+}
+
+// How exercises are compiled and run, set from the command line and info.toml
+#[derive(Clone, Default, Debug)]
+pub struct RunOptions {
+    // The limits applied when running an exercise
+    pub limits: Limits,
+    // Stops compiling or running an exercise when set, used by watch mode
+    pub cancel: Cancel,
+    // How the exercises are compiled
+    pub backend: Backend,
+    // The directory the results of compiling and running exercises are
+    // cached in, None to check them every time
+    pub cache: Option<PathBuf>,
 }
 
 // An enum to track of the state of an Exercise.
@@ -82,148 +215,507 @@ pub struct ContextLine {
 // The result of compiling an exercise
 pub struct CompiledExercise<'a> {
     exercise: &'a Exercise,
-    _handle: FileHandle,
+    options: &'a RunOptions,
+    // The hidden tests compiled into the exercise, if any
+    hidden_tests: Option<&'a Path>,
+    // None if the exercise wasn't compiled as the result was cached
+    binary: Option<Binary>,
+    // Where the result of running the exercise is cached
+    cache: Option<CacheEntry>,
+    // The cached result of compiling and maybe running the exercise
+    cached: Option<Verdict>,
 }
 
-impl<'a> CompiledExercise<'a> {
+impl CompiledExercise<'_> {
     // Run the compiled exercise
     pub fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        self.exercise.run()
+        if let Some(passed) = self.cached.as_ref().and_then(Verdict::passed) {
+            return Ok(passed);
+        }
+        let result = match &self.binary {
+            Some(binary) => self.exercise.run(binary, self.options),
+            // Only compiling it was cached, so it has to be compiled again to run it
+            None => self
+                .exercise
+                .compile_uncached(self.hidden_tests, self.options)
+                .and_then(|binary| self.exercise.run(&binary, self.options)),
+        };
+        // Failing runs aren't cached, they might only fail some of the time
+        if let (Some(cache), Ok(output)) = (&self.cache, &result) {
+            cache.store(Stage::Passed, output);
+        }
+        result
     }
 }
 
+// A compiled binary of an exercise
+struct Binary {
+    // Either in the scratch directory or the target directory of the workspace
+    path: PathBuf,
+    // The environment variables to run it with, set by its build script
+    env: Vec<(String, String)>,
+    // Kept until the binary ran, as it's removed along with the directory
+    _scratch: ScratchDir,
+}
+
 // A representation of an already executed binary
-#[derive(Debug)]
+#[derive(Default, Debug)]
 pub struct ExerciseOutput {
     // The textual contents of the standard output of the binary
     pub stdout: String,
     // The textual contents of the standard error of the binary
     pub stderr: String,
+    // The limit that made us stop the binary early, if any
+    pub exceeded: Option<Exceeded>,
+    // The messages of the compiler, taken out of stdout and stderr
+    pub diagnostics: Vec<Diagnostic>,
 }
 
-struct FileHandle;
+impl ExerciseOutput {
+    pub fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.is_error()).count()
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.is_warning()).count()
+    }
+
+    // The messages of the compiler followed by the rest of stderr, colored
+    // for terminals if `color` is set
+    pub fn messages(&self, color: bool) -> String {
+        let mut messages: String = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.render(color) + "\n")
+            .collect();
+        messages.push_str(&self.stderr);
+        if let Some(counts) = counts(&self.diagnostics) {
+            messages.push_str(&format!("{counts}\n"));
+        }
+        messages
+    }
+}
+
+// Why a running exercise or compilation was stopped early
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Exceeded {
+    // The exercise ran for longer than the given time
+    Timeout(Duration),
+    // The exercise wrote more output than allowed
+    Output,
+    // Someone asked for it to stop, as its result would be outdated
+    Cancelled,
+}
+
+impl Display for Exceeded {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Exceeded::Timeout(timeout) => write!(f, "timed out after {} s", timeout.as_secs_f32()),
+            Exceeded::Output => write!(f, "exceeded the output limit"),
+            Exceeded::Cancelled => write!(f, "was cancelled"),
+        }
+    }
+}
+
+// A directory owned by a single compilation of an exercise.
+// The compiled binary, the generated Cargo.toml and cargo's target directory
+// all live in here, so that exercises can be compiled concurrently without
+// stepping on each other's files. The directory is removed once dropped.
+struct ScratchDir {
+    path: PathBuf,
+}
 
-impl Drop for FileHandle {
+impl ScratchDir {
+    fn new(exercise: &Exercise) -> Self {
+        let path = env::temp_dir().join("rustlings").join(format!(
+            "{}_{}_{}",
+            process::id(),
+            SCRATCH_DIRS.fetch_add(1, Ordering::SeqCst),
+            exercise.name
+        ));
+        fs::create_dir_all(&path).expect("Failed to create a scratch directory for the exercise");
+        ScratchDir { path }
+    }
+
+    // The path of the binary produced by `rustc`
+    fn binary(&self) -> PathBuf {
+        self.path.join(format!("exercise{}", env::consts::EXE_SUFFIX))
+    }
+
+    // The path of the exercise combined with its hidden tests
+    fn source(&self) -> PathBuf {
+        self.path.join("exercise.rs")
+    }
+
+    // The path of the generated manifest for the cargo based modes
+    fn manifest(&self) -> PathBuf {
+        self.path.join("Cargo.toml")
+    }
+
+    // The cargo target directory, private to this compilation
+    fn target_dir(&self) -> PathBuf {
+        self.path.join("target")
+    }
+}
+
+impl Drop for ScratchDir {
     fn drop(&mut self) {
-        clean();
+        let _ignored = fs::remove_dir_all(&self.path);
     }
 }
 
 impl Exercise {
-    pub fn compile(&self) -> Result<CompiledExercise, ExerciseOutput> {
+    pub fn compile<'a>(&'a self, options: &'a RunOptions) -> Result<CompiledExercise<'a>, ExerciseOutput> {
+        self.compile_cached(None, options)
+    }
+
+    // Compile the exercise along with its hidden tests, if it has any.
+    // The hidden tests are appended to a copy of the exercise as a module,
+    // so they can `use super::*` just like the visible tests.
+    pub fn compile_with_hidden_tests<'a>(
+        &'a self,
+        options: &'a RunOptions,
+    ) -> Result<CompiledExercise<'a>, ExerciseOutput> {
+        match (&self.hidden_tests, self.mode) {
+            (Some(hidden_tests), Mode::Test) => self.compile_cached(Some(hidden_tests), options),
+            _ => self.compile(options),
+        }
+    }
+
+    // Compile the exercise, unless the result of compiling it is cached
+    fn compile_cached<'a>(
+        &'a self,
+        hidden_tests: Option<&'a Path>,
+        options: &'a RunOptions,
+    ) -> Result<CompiledExercise<'a>, ExerciseOutput> {
+        let cache = CacheEntry::new(self, hidden_tests, options);
+        let (binary, cached) = match cache.as_ref().and_then(CacheEntry::load) {
+            Some(verdict) if verdict.stage == Stage::CompileFailed => return Err(verdict.output()),
+            Some(verdict) => (None, Some(verdict)),
+            None => {
+                let compiled = self.compile_uncached(hidden_tests, options);
+                if let Some(cache) = &cache {
+                    match &compiled {
+                        Ok(_) => cache.store(Stage::Compiled, &ExerciseOutput::default()),
+                        Err(output) => cache.store(Stage::CompileFailed, output),
+                    }
+                }
+                (Some(compiled?), None)
+            }
+        };
+        Ok(CompiledExercise {
+            exercise: self,
+            options,
+            hidden_tests,
+            binary,
+            cache,
+            cached,
+        })
+    }
+
+    fn compile_uncached(&self, hidden_tests: Option<&Path>, options: &RunOptions) -> Result<Binary, ExerciseOutput> {
+        let hidden_tests = match hidden_tests {
+            Some(hidden_tests) => hidden_tests,
+            None => return self.compile_in(ScratchDir::new(self), &self.path, options),
+        };
+        let scratch = ScratchDir::new(self);
+        // Cargo only compiles the binary targets of the workspace
+        let source = if self.uses_workspace(options.backend) {
+            workspace::hidden_tests_source(self)
+        } else {
+            scratch.source()
+        };
+        let combined = workspace::with_hidden_tests(self, hidden_tests);
+        if let Err(e) = combined.and_then(|combined| workspace::write_if_changed(&source, &combined)) {
+            return Err(ExerciseOutput {
+                stderr: format!("Failed to add the hidden tests to {self}: {e}"),
+                ..ExerciseOutput::default()
+            });
+        }
+        // Point the compiler's complaints at the exercise rather than its copy
+        self.compile_in(scratch, &source, options).map_err(|mut output| {
+            let copy = source.display().to_string();
+            let original = self.path.display().to_string();
+            output.stdout = output.stdout.replace(&copy, &original);
+            output.stderr = output.stderr.replace(&copy, &original);
+            for diagnostic in &mut output.diagnostics {
+                diagnostic.rename_file(&copy, &original);
+            }
+            output
+        })
+    }
+
+    fn compile_in(
+        &self,
+        scratch: ScratchDir,
+        source: &Path,
+        options: &RunOptions,
+    ) -> Result<Binary, ExerciseOutput> {
+        if self.uses_workspace(options.backend) {
+            return self.compile_in_workspace(scratch, source, &options.cancel);
+        }
+        let cancel = &options.cancel;
+        let binary = scratch.binary();
         let cmd = match self.mode {
-            Mode::Compile => Command::new("rustc")
-                .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
-            Mode::Test => Command::new("rustc")
-                .args(&["--test", self.path.to_str().unwrap(), "-o", &temp_file()])
-                .args(RUSTC_COLOR_ARGS)
-                .args(RUSTC_EDITION_ARGS)
-                .output(),
+            Mode::Compile => self.run_compiler(
+                Command::new("rustc")
+                    .arg(source)
+                    .arg("-o")
+                    .arg(&binary)
+                    .args(RUSTC_JSON_ARGS)
+                    .args(RUSTC_EDITION_ARGS),
+                cancel,
+            ),
+            Mode::Test => self.run_compiler(
+                Command::new("rustc")
+                    .arg("--test")
+                    .arg(source)
+                    .arg("-o")
+                    .arg(&binary)
+                    .args(RUSTC_JSON_ARGS)
+                    .args(RUSTC_EDITION_ARGS),
+                cancel,
+            ),
             Mode::Clippy => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
                 let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
                     "Failed to write Clippy Cargo.toml file."
                 } else {
                     "Failed to write 📎 Clippy 📎 Cargo.toml file."
                 };
-                fs::write(CLIPPY_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+                fs::write(scratch.manifest(), self.cargo_toml(false))
+                    .map_err(|e| failed_to_write(cargo_toml_error_msg, e))?;
                 // To support the ability to run the clippy exercises, build
                 // an executable, in addition to running clippy. With a
                 // compilation failure, this would silently fail. But we expect
                 // clippy to reflect the same failure while compiling later.
-                Command::new("rustc")
-                    .args(&[self.path.to_str().unwrap(), "-o", &temp_file()])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(RUSTC_EDITION_ARGS)
-                    .output()
-                    .expect("Failed to compile!");
-                // Due to an issue with Clippy, a cargo clean is required to catch all lints.
-                // See https://github.com/rust-lang/rust-clippy/issues/2604
-                // This is already fixed on Clippy's master branch. See this issue to track merging into Cargo:
-                // https://github.com/rust-lang/rust-clippy/issues/3837
-                Command::new("cargo")
-                    .args(&["clean", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .output()
-                    .expect("Failed to run 'cargo clean'");
-                Command::new("cargo")
-                    .args(&["clippy", "--manifest-path", CLIPPY_CARGO_TOML_PATH])
-                    .args(RUSTC_COLOR_ARGS)
-                    .args(&["--", "-D", "warnings", "-D", "clippy::float_cmp"])
-                    .output()
-            },
+                self.run_compiler(
+                    Command::new("rustc")
+                        .arg(&self.path)
+                        .arg("-o")
+                        .arg(&binary)
+                        .args(RUSTC_JSON_ARGS)
+                        .args(RUSTC_EDITION_ARGS),
+                    cancel,
+                )?;
+                // Clippy only reports lints for crates it actually checks, see
+                // https://github.com/rust-lang/rust-clippy/issues/2604
+                // Every compilation gets a fresh target directory, so nothing
+                // is ever cached and a `cargo clean` isn't needed.
+                self.run_compiler(
+                    Command::new("cargo")
+                        .arg("clippy")
+                        .arg("--manifest-path")
+                        .arg(scratch.manifest())
+                        .arg("--target-dir")
+                        .arg(scratch.target_dir())
+                        .args(CARGO_JSON_ARGS)
+                        .args(["--", "-D", "warnings", "-D", "clippy::float_cmp"]),
+                    cancel,
+                )
+            }
             Mode::BuildScript => {
-                let cargo_toml = format!(
-                    r#"[package]
-name = "{}"
-version = "0.0.1"
-edition = "2021"
-[[bin]]
-name = "{}"
-path = "{}.rs""#,
-                    self.name, self.name, self.name
-                );
-                let cargo_toml_error_msg = if env::var("NO_EMOJI").is_ok() {
-                    "Failed to write Clippy Cargo.toml file."
-                } else {
-                    "Failed to write 📎 Clippy 📎 Cargo.toml file."
-                };
-                fs::write(BUILD_SCRIPT_CARGO_TOML_PATH, cargo_toml).expect(cargo_toml_error_msg);
+                fs::write(scratch.manifest(), self.cargo_toml(true))
+                    .map_err(|e| failed_to_write("Failed to write build script Cargo.toml file.", e))?;
 
-                Command::new("cargo")
-                    .args(&["test", "--manifest-path", BUILD_SCRIPT_CARGO_TOML_PATH])
-                    .output()
+                // Only build the tests, `run` runs them within the limits of the exercise
+                let (success, output, executable) = self
+                    .run_cargo(
+                        Command::new("cargo")
+                            .arg("test")
+                            .arg("--no-run")
+                            .arg("--manifest-path")
+                            .arg(scratch.manifest())
+                            .arg("--target-dir")
+                            .arg(scratch.target_dir())
+                            .args(CARGO_JSON_ARGS),
+                        cancel,
+                    )?;
+                if !success {
+                    return Err(output);
+                }
+                return match executable {
+                    Some(executable) => Ok(Binary {
+                        path: executable.path,
+                        env: executable.env,
+                        _scratch: scratch,
+                    }),
+                    None => Err(ExerciseOutput {
+                        stderr: format!("Cargo didn't report the tests it compiled for {self}"),
+                        ..output
+                    }),
+                };
             }
-        }
-        .expect("Failed to run 'compile' command.");
+        };
+        let (success, output) = cmd?;
 
-        if cmd.status.success() {
-            Ok(CompiledExercise {
-                exercise: self,
-                _handle: FileHandle,
+        if success {
+            Ok(Binary {
+                path: binary,
+                env: Vec::new(),
+                _scratch: scratch,
             })
         } else {
-            clean();
-            Err(ExerciseOutput {
-                stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-                stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
-            })
+            Err(output)
         }
     }
 
-    fn run(&self) -> Result<ExerciseOutput, ExerciseOutput> {
-        let arg = match self.mode {
-            Mode::Test => "--show-output",
-            Mode::BuildScript => return Ok(ExerciseOutput {
-                stdout: "".to_string(),
-                stderr: "".to_string(),
+    // Compile the exercise as a binary target of the workspace. Its target
+    // directory is shared by all exercises and kept between runs, so only
+    // what changed since the last compilation is compiled again.
+    fn compile_in_workspace(
+        &self,
+        scratch: ScratchDir,
+        source: &Path,
+        cancel: &Cancel,
+    ) -> Result<Binary, ExerciseOutput> {
+        let bin = workspace::bin_name(&absolute(source));
+        let cargo = |subcommand: &str| {
+            let mut cmd = Command::new("cargo");
+            cmd.arg(subcommand)
+                .args(["--quiet", "--bin", &bin])
+                .current_dir(WORKSPACE_DIR)
+                .args(CARGO_JSON_ARGS);
+            if workspace::is_vendored() {
+                cmd.arg("--offline");
+            }
+            cmd
+        };
+        let compiled = match self.mode {
+            Mode::Compile | Mode::Clippy => self.run_cargo(&mut cargo("build"), cancel),
+            Mode::Test => self.run_cargo(cargo("test").arg("--no-run"), cancel),
+            Mode::BuildScript => Err(ExerciseOutput {
+                stderr: format!("{self} has a build script, so it gets a package of its own instead of the workspace"),
+                ..ExerciseOutput::default()
             }),
-            _ => "",
         };
-        println!("pa={}", temp_file());
-        let cmd = Command::new(&temp_file())
-            .arg(arg)
-            .output()
-            .expect("Failed to run 'run' command");
-
-        let output = ExerciseOutput {
-            stdout: String::from_utf8_lossy(&cmd.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&cmd.stderr).to_string(),
+        let (success, output, binary) = compiled?;
+        if !success {
+            return Err(output);
+        }
+        if let Mode::Clippy = self.mode {
+            let (success, output, _) = self
+                .run_cargo(cargo("clippy").args(["--", "-D", "warnings", "-D", "clippy::float_cmp"]), cancel)?;
+            if !success {
+                return Err(output);
+            }
+        }
+        match binary {
+            Some(binary) => Ok(Binary {
+                path: binary.path,
+                env: binary.env,
+                _scratch: scratch,
+            }),
+            None => Err(ExerciseOutput {
+                stderr: format!("Cargo didn't report the binary it compiled for {self}"),
+                ..output
+            }),
+        }
+    }
+
+    // Run cargo like `run_compiler`, also returning the binary it compiled
+    fn run_cargo(
+        &self,
+        cmd: &mut Command,
+        cancel: &Cancel,
+    ) -> Result<(bool, ExerciseOutput, Option<Executable>), ExerciseOutput> {
+        let (success, mut output) =
+            run_with_limits(cmd, Duration::MAX, &COMPILER_LIMITS, cancel).map_err(|e| failed_to_start(cmd, e))?;
+        let binary = executable(&output.stdout);
+        extract_diagnostics(&mut output);
+        // Cargo's closing words, which the error counts replace like for rustc
+        output.stderr = output
+            .stderr
+            .lines()
+            .filter(|line| !line.starts_with("error: could not compile"))
+            .map(|line| format!("{line}\n"))
+            .collect();
+        Ok((success, output, binary))
+    }
+
+    // Run a compiler without any limits, other than stopping it when cancelled,
+    // and take its JSON messages out of the output. Not being able to run it
+    // at all, like when it isn't installed, is reported as its output.
+    fn run_compiler(&self, cmd: &mut Command, cancel: &Cancel) -> Result<(bool, ExerciseOutput), ExerciseOutput> {
+        let (success, mut output) =
+            run_with_limits(cmd, Duration::MAX, &COMPILER_LIMITS, cancel).map_err(|e| failed_to_start(cmd, e))?;
+        extract_diagnostics(&mut output);
+        Ok((success, output))
+    }
+
+    // The category of the exercise, which is the directory it lives in unless set explicitly
+    pub fn category(&self) -> String {
+        if let Some(category) = &self.category {
+            return category.clone();
+        }
+        absolute(&self.path)
+            .parent()
+            .and_then(|dir| dir.file_name())
+            .map(|dir| dir.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    // Whether a change to the file at `path` affects the exercise,
+    // because it's the exercise itself or one of the paths it watches
+    pub fn is_affected_by(&self, path: &Path) -> bool {
+        iter::once(&self.path)
+            .chain(&self.watch)
+            .any(|watched| match watched.canonicalize() {
+                Ok(watched) => path.starts_with(watched),
+                Err(_) => path.ends_with(watched),
+            })
+    }
+
+    // The wall-clock time the exercise may run for
+    pub fn timeout(&self, limits: &Limits) -> Duration {
+        self.timeout.map_or(limits.timeout, Duration::from_secs)
+    }
+
+    // Whether the exercise is compiled in the workspace, which it has to be
+    // for using crates. Build script exercises always get a package of their own.
+    pub fn uses_workspace(&self, backend: Backend) -> bool {
+        !matches!(self.mode, Mode::BuildScript)
+            && (backend == Backend::Cargo || !self.dependencies.is_empty())
+    }
+
+    // Generate a Cargo.toml with a single binary target pointing at the
+    // exercise's source file. When `with_build_script` is set, the `build.rs`
+    // living next to the exercise is used as the package's build script.
+    fn cargo_toml(&self, with_build_script: bool) -> String {
+        let source = absolute(&self.path);
+        let build = match source.parent().map(|dir| dir.join("build.rs")) {
+            Some(build_rs) if with_build_script && build_rs.exists() => {
+                format!("build = {:?}\n", build_rs.display().to_string())
+            }
+            _ => String::new(),
         };
+        format!(
+            r#"[package]
+name = "{name}"
+version = "0.0.1"
+edition = "2021"
+{build}[[bin]]
+name = "{name}"
+path = {path:?}
+[workspace]
+[dependencies]
+{dependencies}"#,
+            name = self.name,
+            path = source.display().to_string(),
+            dependencies = workspace::dependencies_table(&self.dependencies),
+        )
+    }
+
+    fn run(&self, binary: &Binary, options: &RunOptions) -> Result<ExerciseOutput, ExerciseOutput> {
+        let arg = match self.mode {
+            Mode::Test | Mode::BuildScript => "--show-output",
+            _ => "",
+        };
+        let mut cmd = Command::new(&binary.path);
+        cmd.arg(arg).envs(binary.env.iter().cloned());
+        let (success, output) = run_with_limits(&mut cmd, self.timeout(&options.limits), &options.limits, &options.cancel)
+            .map_err(|e| failed_to_start(&cmd, e))?;
 
-        if cmd.status.success() {
+        if success {
             Ok(output)
         } else {
             Err(output)
@@ -288,38 +780,217 @@ impl Display for Exercise {
     }
 }
 
-#[inline]
-fn clean() {
-    let _ignored = remove_file(&temp_file());
+// The output explaining why the command couldn't be run
+fn failed_to_start(cmd: &Command, e: io::Error) -> ExerciseOutput {
+    ExerciseOutput {
+        stderr: format!("Failed to run `{}`: {e}", cmd.get_program().to_string_lossy()),
+        ..ExerciseOutput::default()
+    }
+}
+
+// The output explaining why a file needed to compile the exercise couldn't be written
+fn failed_to_write(message: &str, e: io::Error) -> ExerciseOutput {
+    ExerciseOutput {
+        stderr: format!("{message} {e}"),
+        ..ExerciseOutput::default()
+    }
+}
+
+// Run the command to completion while enforcing the given limits.
+// The process is killed as soon as it runs for longer than `timeout`,
+// writes more output than allowed or is cancelled. Returns whether it
+// exited successfully.
+fn run_with_limits(
+    cmd: &mut Command,
+    timeout: Duration,
+    limits: &Limits,
+    cancel: &Cancel,
+) -> io::Result<(bool, ExerciseOutput)> {
+    #[cfg(unix)]
+    if let Some(memory) = limits.memory {
+        use std::os::unix::process::CommandExt;
+        // SAFETY: `setrlimit` is async-signal-safe and nothing is allocated
+        // between forking and executing the exercise.
+        unsafe {
+            cmd.pre_exec(move || {
+                let limit = libc::rlimit {
+                    rlim_cur: memory as libc::rlim_t,
+                    rlim_max: memory as libc::rlim_t,
+                };
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let overflowed = Arc::new(AtomicBool::new(false));
+    let stdout = capture(child.stdout.take().unwrap(), limits.output, &overflowed);
+    let stderr = capture(child.stderr.take().unwrap(), limits.output, &overflowed);
+
+    let start = Instant::now();
+    let (success, exceeded) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status.success(), None);
+        }
+        let exceeded = if cancel.is_cancelled() {
+            Some(Exceeded::Cancelled)
+        } else if overflowed.load(Ordering::SeqCst) {
+            Some(Exceeded::Output)
+        } else if start.elapsed() >= timeout {
+            Some(Exceeded::Timeout(timeout))
+        } else {
+            None
+        };
+        if exceeded.is_some() {
+            let _ignored = child.kill();
+            child.wait()?;
+            break (false, exceeded);
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let output = ExerciseOutput {
+        stdout: String::from_utf8_lossy(&stdout.join().unwrap_or_default()).to_string(),
+        stderr: String::from_utf8_lossy(&stderr.join().unwrap_or_default()).to_string(),
+        exceeded,
+        diagnostics: Vec::new(),
+    };
+    Ok((success, output))
+}
+
+// Take the JSON messages of the compiler out of its output
+fn extract_diagnostics(output: &mut ExerciseOutput) {
+    for text in [&mut output.stdout, &mut output.stderr] {
+        let (diagnostics, rest) = extract(text);
+        output.diagnostics.extend(diagnostics);
+        *text = rest;
+    }
+    if let Ok(dir) = env::current_dir() {
+        for diagnostic in &mut output.diagnostics {
+            diagnostic.relative_to(&dir);
+        }
+    }
+}
+
+// Read everything from the pipe on a separate thread, keeping at most `limit` bytes.
+// Going over the limit is signaled through `overflowed`.
+fn capture(
+    mut pipe: impl Read + Send + 'static,
+    limit: Option<usize>,
+    overflowed: &Arc<AtomicBool>,
+) -> JoinHandle<Vec<u8>> {
+    let overflowed = Arc::clone(overflowed);
+    thread::spawn(move || {
+        let mut captured = Vec::new();
+        let mut buffer = [0; 8192];
+        loop {
+            let read = match pipe.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => read,
+            };
+            let room = limit.map_or(read, |limit| limit.saturating_sub(captured.len()).min(read));
+            captured.extend_from_slice(&buffer[..room]);
+            if room < read {
+                overflowed.store(true, Ordering::SeqCst);
+            }
+        }
+        captured
+    })
+}
+
+// Resolve a path relative to the current directory, as the generated
+// manifests live somewhere else entirely
+pub fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .unwrap_or_else(|_| env::current_dir().unwrap_or_default().join(path))
+}
+
+// An exercise with only the keys info.toml requires, for tests
+#[cfg(test)]
+impl Exercise {
+    pub fn for_test(name: &str, path: impl Into<PathBuf>, mode: Mode) -> Self {
+        Exercise {
+            name: name.to_string(),
+            path: path.into(),
+            mode,
+            hint: String::new(),
+            timeout: None,
+            points: default_points(),
+            category: None,
+            tests_fingerprint: None,
+            hidden_tests: None,
+            watch: Vec::new(),
+            aliases: Vec::new(),
+            dependencies: Default::default(),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::path::Path;
 
     #[test]
     fn test_clean() {
-        File::create(&temp_file()).unwrap();
-        let exercise = Exercise {
-            name: String::from("example"),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::from(""),
-        };
-        let compiled = exercise.compile().unwrap();
+        let exercise = Exercise::for_test("example", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
+        let options = RunOptions::default();
+        let compiled = exercise.compile(&options).unwrap();
+        let scratch = compiled.binary.as_ref().unwrap()._scratch.path.clone();
+        assert!(scratch.exists());
         drop(compiled);
-        assert!(!Path::new(&temp_file()).exists());
+        assert!(!scratch.exists());
     }
 
     #[test]
-    fn test_pending_state() {
+    fn test_missing_compiler() {
+        let exercise = Exercise::for_test("example", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
+        let output = exercise
+            .run_compiler(&mut Command::new("rustlings-missing-compiler"), &Cancel::default())
+            .unwrap_err();
+        assert!(output.stderr.starts_with("Failed to run `rustlings-missing-compiler`"));
+    }
+
+    #[test]
+    fn test_cargo_toml_escapes_paths() {
+        let exercise = Exercise::for_test("example", "tests/fixture/it's \"quoted\"/example.rs", Mode::Compile);
+        let manifest: toml::Value = toml::from_str(&exercise.cargo_toml(false)).unwrap();
+        let path = manifest["bin"][0]["path"].as_str().unwrap();
+        assert!(path.ends_with("tests/fixture/it's \"quoted\"/example.rs"));
+    }
+
+    #[test]
+    fn test_is_affected_by_watched_paths() {
         let exercise = Exercise {
-            name: "pending_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/pending_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
+            watch: vec![PathBuf::from("tests/fixture/success")],
+            ..Exercise::for_test("example", "tests/fixture/state/pending_exercise.rs", Mode::Compile)
         };
+        let affected = |path: &str| exercise.is_affected_by(&Path::new(path).canonicalize().unwrap());
+        assert!(affected("tests/fixture/state/pending_exercise.rs"));
+        assert!(affected("tests/fixture/success/info.toml"));
+        assert!(!affected("tests/fixture/state/finished_exercise.rs"));
+    }
+
+    #[test]
+    fn test_concurrent_compilations_are_isolated() {
+        let exercise = Exercise::for_test("example", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
+        let options = RunOptions::default();
+        let first = exercise.compile(&options).unwrap();
+        let second = exercise.compile(&options).unwrap();
+        assert_ne!(first.binary.as_ref().unwrap().path, second.binary.as_ref().unwrap().path);
+        drop(first);
+        assert!(second.run().is_ok());
+    }
+
+    #[test]
+    fn test_pending_state() {
+        let exercise = Exercise::for_test("pending_exercise", "tests/fixture/state/pending_exercise.rs", Mode::Compile);
 
         let state = exercise.state();
         let expected = vec![
@@ -355,25 +1026,52 @@ mod test {
 
     #[test]
     fn test_finished_exercise() {
-        let exercise = Exercise {
-            name: "finished_exercise".into(),
-            path: PathBuf::from("tests/fixture/state/finished_exercise.rs"),
-            mode: Mode::Compile,
-            hint: String::new(),
-        };
+        let exercise = Exercise::for_test("finished_exercise", "tests/fixture/state/finished_exercise.rs", Mode::Compile);
 
         assert_eq!(exercise.state(), State::Done);
     }
 
     #[test]
     fn test_exercise_with_output() {
+        let exercise = Exercise::for_test("exercise_with_output", "tests/fixture/success/testSuccess.rs", Mode::Test);
+        let out = exercise.compile(&RunOptions::default()).unwrap().run().unwrap();
+        assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
+    }
+
+    #[test]
+    fn test_exercise_timeout() {
         let exercise = Exercise {
-            name: "exercise_with_output".into(),
-            path: PathBuf::from("tests/fixture/success/testSuccess.rs"),
-            mode: Mode::Test,
-            hint: String::new(),
+            timeout: Some(1),
+            ..Exercise::for_test("timeoutExercise", "tests/fixture/failure/timeoutExercise.rs", Mode::Compile)
         };
-        let out = exercise.compile().unwrap().run().unwrap();
-        assert!(out.stdout.contains("THIS TEST TOO SHALL PASS"));
+        let out = exercise.compile(&RunOptions::default()).unwrap().run().unwrap_err();
+        assert_eq!(out.exceeded, Some(Exceeded::Timeout(Duration::from_secs(1))));
+    }
+
+    #[test]
+    fn test_exercise_output_limit() {
+        let exercise = Exercise::for_test("timeoutExercise", "tests/fixture/failure/timeoutExercise.rs", Mode::Compile);
+        let options = RunOptions {
+            limits: Limits {
+                output: Some(16),
+                ..Limits::default()
+            },
+            ..RunOptions::default()
+        };
+        let out = exercise.compile(&options).unwrap().run().unwrap_err();
+        assert_eq!(out.exceeded, Some(Exceeded::Output));
+        assert!(out.stdout.len() <= 16);
+    }
+
+    #[test]
+    fn test_exercise_cancelled() {
+        let exercise = Exercise::for_test("timeoutExercise", "tests/fixture/failure/timeoutExercise.rs", Mode::Compile);
+        let options = RunOptions::default();
+        let compiled = exercise.compile(&options).unwrap();
+        options.cancel.cancel();
+        let out = compiled.run().unwrap_err();
+        assert_eq!(out.exceeded, Some(Exceeded::Cancelled));
+        assert!(options.cancel.reset());
+        assert!(!options.cancel.is_cancelled());
     }
 }
@@ -0,0 +1,204 @@
+use crate::diagnostics::{counts, Diagnostic};
+use crate::exercise::{Exercise, ExerciseOutput};
+use console::{style, Emoji};
+use std::fs;
+use std::process::Command;
+
+// Maps the directories of the exercises to the chapters of the Book
+const CHAPTER_MAPPING: &str = "exercises/README.md";
+
+// The edition of the Book the chapter mapping is numbered after
+const BOOK_URL: &str = "https://doc.rust-lang.org/1.80.0/book";
+
+// The first page of every chapter of the Book, the appendix being chapter 21
+const BOOK_CHAPTERS: [&str; 21] = [
+    "ch01-00-getting-started",
+    "ch02-00-guessing-game-tutorial",
+    "ch03-00-common-programming-concepts",
+    "ch04-00-understanding-ownership",
+    "ch05-00-structs",
+    "ch06-00-enums",
+    "ch07-00-managing-growing-projects-with-packages-crates-and-modules",
+    "ch08-00-common-collections",
+    "ch09-00-error-handling",
+    "ch10-00-generics",
+    "ch11-00-testing",
+    "ch12-00-an-io-project",
+    "ch13-00-functional-features",
+    "ch14-00-more-about-cargo",
+    "ch15-00-smart-pointers",
+    "ch16-00-concurrency",
+    "ch17-00-oop",
+    "ch18-00-patterns",
+    "ch19-00-advanced-features",
+    "ch20-00-final-project-a-web-server",
+    "appendix-00",
+];
+
+// The error codes like E0382 in the output of the compiler, in the order
+// they first appear
+pub fn error_codes(output: &str) -> Vec<&str> {
+    let mut codes = Vec::new();
+    for (start, _) in output.match_indices("[E") {
+        if let Some(code) = error_code_at(output, start) {
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+    }
+    codes
+}
+
+// The error code within the brackets starting at `start`
+fn error_code_at(output: &str, start: usize) -> Option<&str> {
+    let code = output.get(start + 1..start + 6)?;
+    let closed = output[start + 6..].starts_with(']');
+    let digits = code[1..].bytes().all(|b| b.is_ascii_digit());
+    (closed && digits).then_some(code)
+}
+
+// Print the explanation of every error code in the output of the compiler.
+// Returns whether there were any.
+pub fn explain(output: &str) -> bool {
+    let codes = error_codes(output);
+    for code in &codes {
+        if let Err(e) = Command::new("rustc").args(["--explain", code]).status() {
+            println!("Failed to explain {code}: {e}");
+        }
+    }
+    !codes.is_empty()
+}
+
+// The explanations of the error codes in the output as text, like for the
+// output pane of watch mode. None if there are no error codes.
+pub fn explanations(output: &str) -> Option<String> {
+    let codes = error_codes(output);
+    if codes.is_empty() {
+        return None;
+    }
+    let explanations: Vec<String> = codes
+        .iter()
+        .map(|code| match Command::new("rustc").args(["--explain", code]).output() {
+            Ok(explained) => String::from_utf8_lossy(&explained.stdout).into_owned(),
+            Err(e) => format!("Failed to explain {code}: {e}"),
+        })
+        .collect();
+    Some(explanations.join("\n"))
+}
+
+// The first paragraph of the compiler's explanation of the error
+fn summary(diagnostic: &Diagnostic) -> Option<String> {
+    let explanation = diagnostic.code.as_ref()?.explanation.as_deref()?;
+    let summary: Vec<&str> = explanation
+        .lines()
+        .skip_while(|line| line.trim().is_empty())
+        .take_while(|line| !line.trim().is_empty())
+        .map(str::trim)
+        .collect();
+    (!summary.is_empty()).then(|| summary.join(" "))
+}
+
+// The chapters of the Book mapped to the exercises in the given directory,
+// like `§4.1-2`, from the table in the chapter mapping
+fn book_sections<'a>(mapping: &'a str, category: &str) -> Option<&'a str> {
+    mapping.lines().find_map(|line| {
+        let mut cells = line.split('|').map(str::trim).skip(1);
+        match (cells.next(), cells.next()) {
+            (Some(exercises), Some(sections)) if exercises == category => {
+                Some(sections).filter(|sections| sections.starts_with('§'))
+            }
+            _ => None,
+        }
+    })
+}
+
+// The link to the first chapter of the given sections of the Book
+fn book_url(sections: &str) -> Option<String> {
+    let chapter: String = sections
+        .trim_start_matches('§')
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    let page = BOOK_CHAPTERS.get(chapter.parse::<usize>().ok()?.checked_sub(1)?)?;
+    Some(format!("{BOOK_URL}/{page}.html"))
+}
+
+// The messages of the compiler, with a short explanation right under every
+// error with a code and a link to the Book chapter about the exercise at the end
+pub fn annotate(exercise: &Exercise, output: &ExerciseOutput, color: bool) -> String {
+    let mut annotated = String::new();
+    let mut explained = false;
+    for diagnostic in &output.diagnostics {
+        annotated.push_str(&diagnostic.render(color));
+        if let (Some(code), Some(summary)) = (diagnostic.error_code(), summary(diagnostic)) {
+            let code = if color { style(code).bold().to_string() } else { code.to_string() };
+            let at = diagnostic
+                .spans
+                .iter()
+                .find(|span| span.is_primary)
+                .map(|span| format!(" at {}:{}:{}", span.file_name, span.line_start, span.column_start))
+                .unwrap_or_default();
+            annotated.push_str(&format!("{} {code}{at}: {summary}\n", Emoji("💡", "?")));
+            explained = true;
+        }
+        annotated.push('\n');
+    }
+    annotated.push_str(&output.stderr);
+    if let Some(counts) = counts(&output.diagnostics) {
+        annotated.push_str(&format!("{counts}\n"));
+    }
+    match book_chapter(exercise) {
+        Some((sections, url)) if explained => annotated.push_str(&format!(
+            "{} {} is about The Book {sections}: {url}\n",
+            Emoji("📖", "="),
+            exercise.name
+        )),
+        _ => {}
+    }
+    annotated
+}
+
+// The sections of the Book the exercise is about, along with a link to them
+fn book_chapter(exercise: &Exercise) -> Option<(String, String)> {
+    let mapping = fs::read_to_string(CHAPTER_MAPPING).ok()?;
+    let sections = book_sections(&mapping, &exercise.category())?;
+    Some((sections.to_string(), book_url(sections)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        let output = "error[E0382]: borrow of moved value: `vec0`\n\
+            error[E0308]: mismatched types\n\
+            error[E0382]: borrow of moved value: `vec1`\n\
+            error: aborting due to 3 previous errors\n\
+            Some errors have detailed explanations: E0308, E0382.\n\
+            values[E12]";
+        assert_eq!(error_codes(output), ["E0382", "E0308"]);
+        assert!(error_codes("error: expected `;`").is_empty());
+    }
+
+    #[test]
+    fn test_book_sections() {
+        let mapping = "| Exercise               | Book Chapter        |\n\
+            | ---------------------- | ------------------- |\n\
+            | variables              | §3.1                |\n\
+            | move_semantics         | §4.1-2              |\n\
+            | conversions            | n/a                 |\n";
+        assert_eq!(book_sections(mapping, "move_semantics"), Some("§4.1-2"));
+        assert_eq!(book_sections(mapping, "conversions"), None);
+        assert_eq!(book_sections(mapping, "intro"), None);
+        assert_eq!(
+            book_url("§4.1-2").as_deref(),
+            Some("https://doc.rust-lang.org/1.80.0/book/ch04-00-understanding-ownership.html")
+        );
+        assert_eq!(
+            book_url("§21.4").as_deref(),
+            Some("https://doc.rust-lang.org/1.80.0/book/appendix-00.html")
+        );
+        assert_eq!(book_url("n/a"), None);
+    }
+}
@@ -0,0 +1,504 @@
+use crate::exercise::{
+    CategoryRubric, Exceeded, Exercise, ExerciseOutput, Mode, RunOptions, HIDDEN_TESTS_MODULE,
+};
+use crate::integrity::tests_intact;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+// The version of the report's structure.
+// Bump it whenever a field is renamed, removed or changes its meaning.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+// The maximum number of characters kept from an exercise's stdout and stderr
+const EXCERPT_LEN: usize = 4000;
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseCheckList {
+    pub schema_version: u32,
+    pub user_name: Option<String>,
+    pub exercises: Vec<ExerciseResult>,
+    pub categories: Vec<CategoryResult>,
+    pub statistics: ExerciseStatistics,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseResult {
+    pub name: String,
+    pub path: String,
+    pub category: String,
+    pub mode: Mode,
+    pub result: bool,
+    pub failure: Option<FailureKind>,
+    pub points: u32,
+    pub max_points: u32,
+    pub duration_ms: u64,
+    pub stdout: String,
+    pub stderr: String,
+    // The number of errors and warnings of the compiler
+    #[serde(default)]
+    pub errors: usize,
+    #[serde(default)]
+    pub warnings: usize,
+    // The tests that failed, both the visible and the hidden ones
+    #[serde(default)]
+    pub failed_tests: Vec<FailedTest>,
+}
+
+// A test of an exercise that didn't pass
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct FailedTest {
+    // The path of the test, like `tests::adds`
+    pub name: String,
+    // Whether it's one of the hidden tests added when grading
+    pub hidden: bool,
+}
+
+impl ExerciseResult {
+    pub fn new(
+        exercise: &Exercise,
+        failure: Option<FailureKind>,
+        output: &ExerciseOutput,
+        duration: Duration,
+    ) -> Self {
+        ExerciseResult {
+            name: exercise.name.clone(),
+            path: exercise.path.display().to_string(),
+            category: exercise.category(),
+            mode: exercise.mode,
+            result: failure.is_none(),
+            failure,
+            points: if failure.is_none() { exercise.points } else { 0 },
+            max_points: exercise.points,
+            duration_ms: duration.as_millis() as u64,
+            stdout: excerpt(&output.stdout),
+            stderr: excerpt(&output.messages(false)),
+            errors: output.errors(),
+            warnings: output.warnings(),
+            failed_tests: match failure {
+                Some(FailureKind::TestFailure | FailureKind::HiddenTestFailure) => {
+                    failed_tests(&output.stdout)
+                }
+                _ => Vec::new(),
+            },
+        }
+    }
+}
+
+// The score of all exercises sharing a category
+#[derive(Deserialize, Serialize)]
+pub struct CategoryResult {
+    pub name: String,
+    pub total: usize,
+    pub succeeds: usize,
+    pub failures: usize,
+    pub points: u32,
+    pub max_points: u32,
+    // The number of exercises that need to pass, according to the rubric
+    pub required: Option<usize>,
+    pub passed: bool,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ExerciseStatistics {
+    pub total_exercises: usize,
+    pub total_succeeds: usize,
+    pub total_failures: usize,
+    pub total_points: u32,
+    pub max_points: u32,
+    // Whether every category met its requirement
+    pub passed: bool,
+    pub total_time_ms: u64,
+}
+
+// The reason an exercise didn't pass
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    // The exercise doesn't compile
+    CompileError,
+    // The exercise compiles, but its tests don't pass
+    TestFailure,
+    // Some of the hidden tests added when grading don't pass, whether the
+    // visible ones do or not
+    HiddenTestFailure,
+    // Clippy isn't happy with the exercise
+    ClippyLint,
+    // The compiled exercise exited with an error
+    RuntimeError,
+    // The exercise ran for longer than its timeout
+    Timeout,
+    // The exercise wrote more output than allowed
+    OutputLimit,
+    // The exercise is still marked with `I AM NOT DONE`
+    NotDone,
+    // The tests of the exercise don't match their fingerprint in `info.toml`
+    TamperedTests,
+    // Checking the exercise was stopped for a newer version of it, only in watch mode
+    Cancelled,
+}
+
+impl Display for FailureKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let description = match self {
+            FailureKind::CompileError => "compile error",
+            FailureKind::TestFailure => "test failure",
+            FailureKind::HiddenTestFailure => "hidden test failure",
+            FailureKind::ClippyLint => "clippy lint",
+            FailureKind::RuntimeError => "runtime error",
+            FailureKind::Timeout => "timed out",
+            FailureKind::OutputLimit => "output limit exceeded",
+            FailureKind::NotDone => "marked as not done",
+            FailureKind::TamperedTests => "tests were modified",
+            FailureKind::Cancelled => "cancelled",
+        };
+        write!(f, "{description}")
+    }
+}
+
+// The number of exercises graded at the same time when `--jobs` isn't given
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+// Compile and run every exercise on a pool of at most `jobs` workers.
+// Each compilation happens in its own scratch directory, so exercises
+// don't interfere with each other, and the results are reported in the
+// order of `info.toml` no matter which exercise finished first.
+pub async fn grade(
+    exercises: Vec<Exercise>,
+    rubric: &[CategoryRubric],
+    jobs: usize,
+    options: &RunOptions,
+) -> ExerciseCheckList {
+    let start = Instant::now();
+    let total = exercises.len();
+    let workers = Arc::new(Semaphore::new(jobs.max(1)));
+    let succeeds = Arc::new(AtomicUsize::new(0));
+
+    let mut tasks = Vec::with_capacity(total);
+    for exercise in exercises {
+        let workers = Arc::clone(&workers);
+        let succeeds = Arc::clone(&succeeds);
+        let options = options.clone();
+        tasks.push(tokio::spawn(async move {
+            let _permit = workers.acquire_owned().await.unwrap();
+            tokio::task::spawn_blocking(move || {
+                let exercise_start = Instant::now();
+                let (failure, output) = grade_exercise(&exercise, &options);
+                let duration = exercise_start.elapsed();
+                match failure {
+                    None => {
+                        succeeds.fetch_add(1, Ordering::SeqCst);
+                        println!("{}执行成功", exercise.name);
+                    }
+                    Some(FailureKind::Timeout) => println!("{}执行超时", exercise.name),
+                    Some(_) => println!("{}执行失败", exercise.name),
+                }
+                println!("总的题目数: {}", total);
+                println!("当前做正确的题目数: {}", succeeds.load(Ordering::SeqCst));
+                println!("当前修改试卷耗时: {} ms", duration.as_millis());
+                ExerciseResult::new(&exercise, failure, &output, duration)
+            })
+            .await
+            .unwrap()
+        }));
+    }
+
+    let mut results = Vec::with_capacity(total);
+    for task in tasks {
+        results.push(task.await.unwrap());
+    }
+    let total_succeeds = results.iter().filter(|r| r.result).count();
+    let total_time = start.elapsed();
+    let categories = categories(&results, rubric);
+    let statistics = ExerciseStatistics {
+        total_exercises: total,
+        total_succeeds,
+        total_failures: total - total_succeeds,
+        total_points: results.iter().map(|r| r.points).sum(),
+        max_points: results.iter().map(|r| r.max_points).sum(),
+        passed: categories.iter().all(|c| c.passed),
+        total_time_ms: total_time.as_millis() as u64,
+    };
+    println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time.as_secs());
+    println!("总得分: {} / {}", statistics.total_points, statistics.max_points);
+    for category in categories.iter().filter(|c| !c.passed) {
+        println!(
+            "{}未达到要求: 需要完成 {} 道题, 实际完成 {} 道题",
+            category.name,
+            category.required.unwrap_or_default(),
+            category.succeeds
+        );
+    }
+
+    ExerciseCheckList {
+        schema_version: REPORT_SCHEMA_VERSION,
+        user_name: None,
+        exercises: results,
+        categories,
+        statistics,
+    }
+}
+
+// Compile the exercise and run the resulting binary without printing anything.
+// Returns why the exercise failed, if it did, along with its output.
+// Grading never reads the cache, as anyone could write a passing verdict there.
+fn grade_exercise(exercise: &Exercise, options: &RunOptions) -> (Option<FailureKind>, ExerciseOutput) {
+    let options = &RunOptions {
+        cache: None,
+        ..options.clone()
+    };
+    if !tests_intact(exercise) {
+        let output = ExerciseOutput {
+            stderr: format!(
+                "The tests of {exercise} were modified, restore them to get this exercise graded"
+            ),
+            ..ExerciseOutput::default()
+        };
+        return (Some(FailureKind::TamperedTests), output);
+    }
+    if !exercise.looks_done() {
+        return (Some(FailureKind::NotDone), ExerciseOutput::default());
+    }
+
+    let compiled = match exercise.compile_with_hidden_tests(options) {
+        Ok(compiled) => compiled,
+        Err(output) => return (Some(compile_failure(exercise, &output)), output),
+    };
+
+    match compiled.run() {
+        Ok(output) => (None, output),
+        Err(output) => (Some(run_failure(exercise, &output)), output),
+    }
+}
+
+// Tell why compiling the exercise failed from the compiler's output
+pub fn compile_failure(exercise: &Exercise, output: &ExerciseOutput) -> FailureKind {
+    if output.exceeded == Some(Exceeded::Cancelled) {
+        return FailureKind::Cancelled;
+    }
+    match exercise.mode {
+        Mode::Clippy => FailureKind::ClippyLint,
+        _ => FailureKind::CompileError,
+    }
+}
+
+// Tell why running the compiled exercise failed
+pub fn run_failure(exercise: &Exercise, output: &ExerciseOutput) -> FailureKind {
+    match (output.exceeded, exercise.mode) {
+        (Some(Exceeded::Timeout(_)), _) => FailureKind::Timeout,
+        (Some(Exceeded::Output), _) => FailureKind::OutputLimit,
+        (Some(Exceeded::Cancelled), _) => FailureKind::Cancelled,
+        (None, Mode::Test) if failed_tests(&output.stdout).iter().any(|test| test.hidden) => {
+            FailureKind::HiddenTestFailure
+        }
+        (None, Mode::Test | Mode::BuildScript) => FailureKind::TestFailure,
+        (None, _) => FailureKind::RuntimeError,
+    }
+}
+
+// The failing tests reported by the test harness, telling the hidden ones apart
+fn failed_tests(stdout: &str) -> Vec<FailedTest> {
+    let hidden = format!("{HIDDEN_TESTS_MODULE}::");
+    stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("test ")?.strip_suffix(" ... FAILED"))
+        .map(|test| FailedTest {
+            name: test.to_string(),
+            hidden: test.starts_with(&hidden),
+        })
+        .collect()
+}
+
+// Sum up the results per category, in the order the categories first appear,
+// and check them against the rubric
+fn categories(results: &[ExerciseResult], rubric: &[CategoryRubric]) -> Vec<CategoryResult> {
+    let mut categories: Vec<CategoryResult> = Vec::new();
+    for result in results {
+        let index = match categories.iter().position(|c| c.name == result.category) {
+            Some(index) => index,
+            None => {
+                categories.push(CategoryResult {
+                    name: result.category.clone(),
+                    total: 0,
+                    succeeds: 0,
+                    failures: 0,
+                    points: 0,
+                    max_points: 0,
+                    required: rubric
+                        .iter()
+                        .find(|r| r.name == result.category)
+                        .map(|r| r.required),
+                    passed: true,
+                });
+                categories.len() - 1
+            }
+        };
+        let category = &mut categories[index];
+        category.total += 1;
+        category.points += result.points;
+        category.max_points += result.max_points;
+        if result.result {
+            category.succeeds += 1;
+        } else {
+            category.failures += 1;
+        }
+    }
+    for category in &mut categories {
+        category.passed = category.succeeds >= category.required.unwrap_or(0);
+    }
+    categories
+}
+
+// Strip the colors from the output and keep only its beginning
+fn excerpt(output: &str) -> String {
+    let output = console::strip_ansi_codes(output);
+    match output.char_indices().nth(EXCERPT_LEN) {
+        Some((end, _)) => format!("{}\n...", &output[..end]),
+        None => output.into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cache::{CacheEntry, Stage};
+    use std::path::PathBuf;
+
+    fn exercise(path: &str, mode: Mode) -> Exercise {
+        Exercise {
+            timeout: Some(1),
+            ..Exercise::for_test("example", path, mode)
+        }
+    }
+
+    #[test]
+    fn test_grade_success() {
+        let (failure, _) = grade_exercise(&exercise("tests/fixture/success/testSuccess.rs", Mode::Test), &RunOptions::default());
+        assert_eq!(failure, None);
+    }
+
+    #[test]
+    fn test_grade_failures() {
+        let cases = [
+            ("tests/fixture/failure/compFailure.rs", Mode::Compile, FailureKind::CompileError),
+            ("tests/fixture/failure/testNotPassed.rs", Mode::Test, FailureKind::TestFailure),
+            ("tests/fixture/failure/timeoutExercise.rs", Mode::Compile, FailureKind::Timeout),
+            ("tests/fixture/failure/loudExercise.rs", Mode::Compile, FailureKind::OutputLimit),
+            ("tests/fixture/failure/build_script/loopingTest.rs", Mode::BuildScript, FailureKind::Timeout),
+            ("tests/fixture/state/pending_exercise.rs", Mode::Compile, FailureKind::NotDone),
+        ];
+        for (path, mode, expected) in cases {
+            let (failure, _) = grade_exercise(&exercise(path, mode), &RunOptions::default());
+            assert_eq!(failure, Some(expected), "{path}");
+        }
+    }
+
+    #[test]
+    fn test_grade_ignores_the_cache() {
+        let failing = exercise("tests/fixture/failure/testNotPassed.rs", Mode::Test);
+        // A forged verdict in a cache of the test's own
+        let cache = std::env::temp_dir().join(format!("rustlings_grade_cache_{}", std::process::id()));
+        let options = RunOptions {
+            cache: Some(cache.clone()),
+            ..RunOptions::default()
+        };
+        let entry = CacheEntry::new(&failing, None, &options).unwrap();
+        entry.store(Stage::Compiled, &ExerciseOutput::default());
+        entry.store(Stage::Passed, &ExerciseOutput::default());
+        let (failure, _) = grade_exercise(&failing, &options);
+        assert_eq!(failure, Some(FailureKind::TestFailure));
+        std::fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn test_grade_tampered_tests() {
+        let path = "tests/fixture/success/testSuccess.rs";
+        let mut tampered = exercise(path, Mode::Test);
+        tampered.tests_fingerprint = Some("0".repeat(64));
+        let (failure, output) = grade_exercise(&tampered, &RunOptions::default());
+        assert_eq!(failure, Some(FailureKind::TamperedTests));
+        assert!(output.stderr.contains("were modified"));
+
+        let mut intact = exercise(path, Mode::Test);
+        let source = std::fs::read_to_string(path).unwrap();
+        intact.tests_fingerprint = Some(crate::integrity::tests_fingerprint(&source));
+        assert_eq!(grade_exercise(&intact, &RunOptions::default()).0, None);
+    }
+
+    #[test]
+    fn test_grade_hidden_tests() {
+        let path = "tests/fixture/hidden/addExercise.rs";
+        let mut passing = exercise(path, Mode::Test);
+        passing.hidden_tests = Some(PathBuf::from("tests/fixture/hidden/grading/add_hidden.rs"));
+        assert_eq!(grade_exercise(&passing, &RunOptions::default()).0, None);
+
+        let mut failing = exercise(path, Mode::Test);
+        failing.hidden_tests = Some(PathBuf::from(
+            "tests/fixture/hidden/grading/add_hidden_failing.rs",
+        ));
+        let (failure, output) = grade_exercise(&failing, &RunOptions::default());
+        assert_eq!(failure, Some(FailureKind::HiddenTestFailure));
+        let result = ExerciseResult::new(&failing, failure, &output, Duration::ZERO);
+        assert_eq!(
+            result.failed_tests,
+            [FailedTest {
+                name: "hidden_tests::adds_negative_numbers".to_string(),
+                hidden: true
+            }]
+        );
+    }
+
+    #[test]
+    fn test_failed_tests() {
+        let failed = |name: &str, hidden| FailedTest {
+            name: name.to_string(),
+            hidden,
+        };
+        let hidden = "test tests::adds ... ok\ntest hidden_tests::adds ... FAILED\n";
+        assert_eq!(failed_tests(hidden), [failed("hidden_tests::adds", true)]);
+        let both = "test tests::adds ... FAILED\ntest hidden_tests::adds ... FAILED\n";
+        assert_eq!(
+            failed_tests(both),
+            [failed("tests::adds", false), failed("hidden_tests::adds", true)]
+        );
+        assert_eq!(failed_tests("test result: FAILED\n"), []);
+    }
+
+    #[test]
+    fn test_categories_rubric() {
+        let mut algorithm = exercise("tests/fixture/success/testSuccess.rs", Mode::Test);
+        algorithm.category = Some("algorithm".into());
+        algorithm.points = 5;
+        let output = ExerciseOutput::default();
+        let results = [
+            ExerciseResult::new(&algorithm, None, &output, Duration::ZERO),
+            ExerciseResult::new(&algorithm, Some(FailureKind::TestFailure), &output, Duration::ZERO),
+            ExerciseResult::new(&exercise("tests/fixture/success/compSuccess.rs", Mode::Compile), None, &output, Duration::ZERO),
+        ];
+        let rubric = [CategoryRubric {
+            name: "algorithm".into(),
+            required: 2,
+        }];
+
+        let categories = categories(&results, &rubric);
+        assert_eq!(categories.len(), 2);
+        assert_eq!(categories[0].name, "algorithm");
+        assert_eq!((categories[0].points, categories[0].max_points), (5, 10));
+        assert!(!categories[0].passed);
+        assert_eq!(categories[1].name, "success");
+        assert!(categories[1].passed);
+    }
+
+    #[test]
+    fn test_excerpt() {
+        assert_eq!(excerpt("\x1b[31merror\x1b[0m"), "error");
+        let long = "a".repeat(EXCERPT_LEN + 1);
+        assert_eq!(excerpt(&long), format!("{}\n...", "a".repeat(EXCERPT_LEN)));
+    }
+}
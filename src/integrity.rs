@@ -0,0 +1,329 @@
+use crate::exercise::Exercise;
+use crate::progress::hash;
+use std::fs;
+
+// The attribute marking the items that make up an exercise's tests
+const TEST_ATTRIBUTE: &str = "#[cfg(test)]";
+// Tests can also be functions of their own, outside of a `#[cfg(test)]`
+// module. Some exercises are solved inside them, so only their signature and
+// their assertions are part of the fingerprint.
+const TEST_FUNCTION: &str = "#[test]";
+// The macros whose calls are the assertions of a test, like `assert_eq!`
+const ASSERTION: &str = "assert";
+// Macros can replace the ones the tests use, like `assert_eq!`, from
+// outside of them
+const MACRO_DEFINITION: &str = "macro_rules!";
+
+// A piece of source code: a literal, kept as it's written, or any other
+// character that isn't whitespace or part of a comment
+#[derive(PartialEq, Debug)]
+enum Piece<'a> {
+    Literal(&'a str),
+    Code(char),
+}
+
+// Compute the fingerprint of the tests in the source.
+// These are all items annotated with `#[cfg(test)]`, usually `mod tests`,
+// the assertions of `#[test]` functions outside of them and all macro
+// definitions.
+// Comments and formatting don't change the fingerprint, anything else does.
+pub fn tests_fingerprint(source: &str) -> String {
+    let pieces = pieces(source);
+    let starts: Vec<Vec<Piece>> = [TEST_ATTRIBUTE, TEST_FUNCTION, MACRO_DEFINITION]
+        .iter()
+        .map(|start| start.chars().map(Piece::Code).collect())
+        .collect();
+    let assertion: Vec<Piece> = ASSERTION.chars().map(Piece::Code).collect();
+
+    let mut tests = String::new();
+    let mut i = 0;
+    while i < pieces.len() {
+        let start = starts
+            .iter()
+            .find(|start| pieces[i..].starts_with(start) && !follows_identifier(&pieces, i));
+        let start = match start {
+            Some(start) => start,
+            None => {
+                i += 1;
+                continue;
+            }
+        };
+        let end = item_end(&pieces, i + start.len());
+        if *start == starts[1] {
+            let body = (i..end).find(|&j| pieces[j] == Piece::Code('{')).unwrap_or(end);
+            push(&mut tests, &pieces[i..body]);
+            let mut j = body;
+            while j < end {
+                if pieces[j..end].starts_with(&assertion) && !follows_identifier(&pieces, j) {
+                    let assertion_end = item_end(&pieces, j).min(end);
+                    push(&mut tests, &pieces[j..assertion_end]);
+                    j = assertion_end;
+                } else {
+                    j += 1;
+                }
+            }
+        } else {
+            push(&mut tests, &pieces[i..end]);
+        }
+        i = end;
+    }
+    hash(&tests)
+}
+
+// Add the pieces to the tests, on a line of their own
+fn push(tests: &mut String, pieces: &[Piece]) {
+    for piece in pieces {
+        match piece {
+            Piece::Literal(literal) => tests.push_str(literal),
+            Piece::Code(c) => tests.push(*c),
+        }
+    }
+    tests.push('\n');
+}
+
+// Whether the tests of the exercise still match the fingerprint in `info.toml`.
+// Exercises without a fingerprint are always considered intact.
+pub fn tests_intact(exercise: &Exercise) -> bool {
+    let fingerprint = match &exercise.tests_fingerprint {
+        Some(fingerprint) => fingerprint,
+        None => return true,
+    };
+    match fs::read_to_string(&exercise.path) {
+        Ok(source) => tests_fingerprint(&source) == *fingerprint,
+        Err(_) => false,
+    }
+}
+
+// Whether the piece at `i` continues an identifier, like `my_macro_rules!`
+fn follows_identifier(pieces: &[Piece], i: usize) -> bool {
+    match i.checked_sub(1).map(|previous| &pieces[previous]) {
+        Some(Piece::Code(c)) => c.is_alphanumeric() || *c == '_',
+        _ => false,
+    }
+}
+
+// Find the end of the item starting at `start`, which is either the brace
+// closing its body or the semicolon ending it, like the one after
+// `macro_rules! m ( .. );`
+fn item_end(pieces: &[Piece], start: usize) -> usize {
+    let mut depth = 0;
+    for (i, piece) in pieces.iter().enumerate().skip(start) {
+        match piece {
+            Piece::Code('{' | '(' | '[') => depth += 1,
+            Piece::Code(')' | ']') => depth -= 1,
+            Piece::Code('}') => {
+                depth -= 1;
+                if depth <= 0 {
+                    return i + 1;
+                }
+            }
+            Piece::Code(';') if depth <= 0 => return i + 1,
+            _ => {}
+        }
+    }
+    pieces.len()
+}
+
+// Split the source into pieces, dropping whitespace and comments
+fn pieces(source: &str) -> Vec<Piece<'_>> {
+    let mut pieces = Vec::new();
+    let mut i = 0;
+    while i < source.len() {
+        let rest = &source[i..];
+        let c = rest.chars().next().unwrap();
+        if c.is_whitespace() {
+            i += c.len_utf8();
+        } else if rest.starts_with("//") {
+            i += rest.find('\n').unwrap_or(rest.len());
+        } else if rest.starts_with("/*") {
+            i += block_comment_len(rest);
+        } else if c == '"' {
+            let len = string_len(rest);
+            pieces.push(Piece::Literal(&source[i..i + len]));
+            i += len;
+        } else if let Some(len) = raw_string_len(rest) {
+            pieces.push(Piece::Literal(&source[i..i + len]));
+            i += len;
+        } else if let Some(len) = char_len(rest) {
+            pieces.push(Piece::Literal(&source[i..i + len]));
+            i += len;
+        } else {
+            pieces.push(Piece::Code(c));
+            i += c.len_utf8();
+        }
+    }
+    pieces
+}
+
+// The length of the (possibly nested) block comment at the start of `rest`
+fn block_comment_len(rest: &str) -> usize {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < rest.len() {
+        if rest[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if rest[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += rest[i..].chars().next().unwrap().len_utf8();
+        }
+    }
+    rest.len()
+}
+
+// The length of the string literal at the start of `rest`, including its quotes
+fn string_len(rest: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in rest.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return i + 1,
+            _ => {}
+        }
+    }
+    rest.len()
+}
+
+// The length of the raw string literal like `r#"..."#` or `br"..."` at the
+// start of `rest`, if there is one
+fn raw_string_len(rest: &str) -> Option<usize> {
+    let after_prefix = rest.strip_prefix("br").or_else(|| rest.strip_prefix('r'))?;
+    let hashes = after_prefix.len() - after_prefix.trim_start_matches('#').len();
+    let body = after_prefix[hashes..].strip_prefix('"')?;
+    let terminator = format!("\"{}", "#".repeat(hashes));
+    let end = body.find(&terminator).map_or(body.len(), |end| end + terminator.len());
+    Some(rest.len() - body.len() + end)
+}
+
+// The length of the char literal at the start of `rest`, if there is one.
+// A quote that doesn't start a char literal belongs to a lifetime.
+fn char_len(rest: &str) -> Option<usize> {
+    let body = rest.strip_prefix('\'')?;
+    let len = if let Some(escape) = body.strip_prefix('\\') {
+        // Skip the escaped character, which might be a quote itself
+        let escaped = escape.chars().next()?.len_utf8();
+        1 + escaped + escape[escaped..].find('\'')?
+    } else {
+        let c = body.chars().next()?;
+        if !body[c.len_utf8()..].starts_with('\'') {
+            return None;
+        }
+        c.len_utf8()
+    };
+    Some(len + 2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::ExerciseList;
+    use std::{env, process};
+
+    const EXERCISE: &str = r#"
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        assert_eq!(add(1, 2), 3, "{} isn't 3", add(1, 2));
+    }
+}
+"#;
+
+    #[test]
+    fn test_fingerprint_ignores_formatting_and_comments() {
+        let reformatted = EXERCISE
+            .replace("    ", "\t")
+            .replace("fn adds() {", "// A comment with a {\nfn adds()\n{");
+        assert_eq!(tests_fingerprint(EXERCISE), tests_fingerprint(&reformatted));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_solution() {
+        let solved = EXERCISE.replace("a + b", "b + a");
+        assert_eq!(tests_fingerprint(EXERCISE), tests_fingerprint(&solved));
+    }
+
+    #[test]
+    fn test_fingerprint_detects_tampering() {
+        let tampered = EXERCISE.replace("assert_eq!(add(1, 2), 3", "assert_eq!(3, 3");
+        assert_ne!(tests_fingerprint(EXERCISE), tests_fingerprint(&tampered));
+
+        let removed = &EXERCISE[..EXERCISE.find("#[cfg(test)]").unwrap()];
+        assert_ne!(tests_fingerprint(EXERCISE), tests_fingerprint(removed));
+    }
+
+    #[test]
+    fn test_fingerprint_detects_replaced_macros() {
+        let replaced = format!("macro_rules! assert_eq {{\n    ($($t:tt)*) => {{}};\n}}\n{EXERCISE}");
+        assert_ne!(tests_fingerprint(EXERCISE), tests_fingerprint(&replaced));
+
+        let parenthesized = format!("macro_rules! assert_eq (\n    ($($t:tt)*) => {{}};\n);\n{EXERCISE}");
+        assert_ne!(tests_fingerprint(EXERCISE), tests_fingerprint(&parenthesized));
+        assert_ne!(tests_fingerprint(&replaced), tests_fingerprint(&parenthesized));
+    }
+
+    #[test]
+    fn test_fingerprint_covers_top_level_tests() {
+        let exercise = r#"
+#[test]
+fn slices() {
+    let a = [1, 2, 3, 4, 5];
+    let slice = ???;
+    assert_eq!([2, 3, 4], slice);
+}
+"#;
+        let solved = exercise.replace("???", "&a[1..4]");
+        assert_eq!(tests_fingerprint(exercise), tests_fingerprint(&solved));
+
+        let tampered = solved.replace("[2, 3, 4], slice", "slice, slice");
+        assert_ne!(tests_fingerprint(&solved), tests_fingerprint(&tampered));
+
+        let ignored = solved.replace("#[test]", "#[test]\n#[ignore]");
+        assert_ne!(tests_fingerprint(&solved), tests_fingerprint(&ignored));
+    }
+
+    #[test]
+    fn test_tampered_exercise_is_not_intact() {
+        let manifest = fs::read_to_string("tracks/basics.toml").unwrap();
+        let list: ExerciseList = toml::from_str(&manifest).unwrap();
+        let mut exercise = list
+            .exercises
+            .into_iter()
+            .find(|exercise| exercise.name == "primitive_types4")
+            .unwrap();
+        let source = fs::read_to_string("curriculum/exercises/primitive_types/primitive_types4.rs").unwrap();
+
+        let dir = env::temp_dir().join(format!("rustlings_integrity_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        exercise.path = dir.join("primitive_types4.rs");
+        fs::write(&exercise.path, source.replace("???", "&a[1..4];")).unwrap();
+        assert!(tests_intact(&exercise));
+
+        let tampered = source.replace("[2, 3, 4], nice_slice", "nice_slice, nice_slice");
+        assert_ne!(tampered, source);
+        fs::write(&exercise.path, tampered).unwrap();
+        assert!(!tests_intact(&exercise));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pieces_keep_literals() {
+        let pieces = pieces(r##"let s = r#"a { b"#; let c = '{'; fn f<'a>() {}"##);
+        assert!(pieces.contains(&Piece::Literal(r##"r#"a { b"#"##)));
+        assert!(pieces.contains(&Piece::Literal("'{'")));
+        assert_eq!(super::pieces(r"'\''"), vec![Piece::Literal(r"'\''")]);
+        assert_eq!(pieces.iter().filter(|p| **p == Piece::Code('{')).count(), 1);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::exercise::Exercise;
+use crate::grade::ExerciseResult;
+use crate::report::{output, seconds};
+use std::fmt::Write as _;
+
+// A single <testcase> of the report
+enum TestCase<'a> {
+    Verified(&'a ExerciseResult),
+    // An exercise that wasn't verified, as an earlier one failed
+    Skipped(&'a Exercise),
+}
+
+impl TestCase<'_> {
+    fn category(&self) -> String {
+        match self {
+            TestCase::Verified(result) => result.category.clone(),
+            TestCase::Skipped(exercise) => exercise.category(),
+        }
+    }
+}
+
+// Render the results as JUnit XML.
+// Every exercise becomes a <testcase>, grouped into one <testsuite> per
+// category in the order the categories first appear. Failed exercises carry
+// the compiler or test harness output in their <failure> element.
+pub fn render(results: &[ExerciseResult], skipped: &[&Exercise]) -> String {
+    let cases: Vec<(String, TestCase)> = results
+        .iter()
+        .map(TestCase::Verified)
+        .chain(skipped.iter().map(|exercise| TestCase::Skipped(exercise)))
+        .map(|case| (case.category(), case))
+        .collect();
+    let mut categories: Vec<&str> = Vec::new();
+    for (category, _) in &cases {
+        if !categories.contains(&category.as_str()) {
+            categories.push(category);
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="rustlings" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+        cases.len(),
+        results.iter().filter(|r| !r.result).count(),
+        skipped.len(),
+        seconds(results.iter().map(|r| r.duration_ms).sum()),
+    );
+    for category in categories {
+        let suite: Vec<&TestCase> = cases
+            .iter()
+            .filter(|(c, _)| c == category)
+            .map(|(_, case)| case)
+            .collect();
+        let verified = suite.iter().filter_map(|case| match case {
+            TestCase::Verified(result) => Some(result),
+            TestCase::Skipped(_) => None,
+        });
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+            escape(category),
+            suite.len(),
+            verified.clone().filter(|r| !r.result).count(),
+            suite.len() - verified.clone().count(),
+            seconds(verified.map(|r| r.duration_ms).sum()),
+        );
+        for case in suite {
+            match case {
+                TestCase::Verified(result) => testcase(&mut xml, result),
+                TestCase::Skipped(exercise) => {
+                    let _ = writeln!(
+                        xml,
+                        r#"    <testcase name="{}" classname="{}" file="{}">"#,
+                        escape(&exercise.name),
+                        escape(category),
+                        escape(&exercise.path.display().to_string()),
+                    );
+                    xml.push_str("      <skipped />\n    </testcase>\n");
+                }
+            }
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn testcase(xml: &mut String, result: &ExerciseResult) {
+    let _ = write!(
+        xml,
+        r#"    <testcase name="{}" classname="{}" file="{}" time="{}""#,
+        escape(&result.name),
+        escape(&result.category),
+        escape(&result.path),
+        seconds(result.duration_ms),
+    );
+    match result.failure {
+        None => xml.push_str(" />\n"),
+        Some(failure) => {
+            let _ = writeln!(
+                xml,
+                ">\n      <failure type=\"{failure:?}\" message=\"{failure}\">{}</failure>\n    </testcase>",
+                escape(&output(result)),
+            );
+        }
+    }
+}
+
+// Escape text for use in XML, dropping the characters XML can't represent at all
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::{ExerciseOutput, Mode};
+    use crate::grade::FailureKind;
+    use std::time::Duration;
+
+    fn exercise(name: &str, path: &str) -> Exercise {
+        Exercise::for_test(name, path, Mode::Compile)
+    }
+
+    #[test]
+    fn test_render_groups_by_category() {
+        let passed = exercise("intro2", "exercises/intro/intro2.rs");
+        let failed = exercise("variables1", "exercises/variables/variables1.rs");
+        let skipped = exercise("variables2", "exercises/variables/variables2.rs");
+        let output = ExerciseOutput {
+            stderr: "error[E0425]: cannot find value `x` <in this scope>".into(),
+            ..ExerciseOutput::default()
+        };
+        let results = [
+            ExerciseResult::new(&passed, None, &ExerciseOutput::default(), Duration::ZERO),
+            ExerciseResult::new(&failed, Some(FailureKind::CompileError), &output, Duration::ZERO),
+        ];
+
+        let xml = render(&results, &[&skipped]);
+        assert!(xml.contains(r#"<testsuite name="intro" tests="1" failures="0" skipped="0""#));
+        assert!(xml.contains(r#"<testsuite name="variables" tests="2" failures="1" skipped="1""#));
+        assert!(xml.contains("cannot find value `x` &lt;in this scope&gt;</failure>"));
+        assert!(xml.contains(r#"<testcase name="variables2" classname="variables" file="exercises/variables/variables2.rs">"#));
+    }
+}
@@ -0,0 +1,227 @@
+use std::io::{self, IsTerminal, Read, Write};
+
+// Turns off the terminal's own line editing, so that keys like tab, the arrows
+// and ctrl-c reach the line editor instead. The terminal is restored once dropped.
+pub struct EditingMode {
+    #[cfg(unix)]
+    original: libc::termios,
+}
+
+impl EditingMode {
+    // Nothing is changed if stdin isn't a terminal or the platform isn't supported
+    #[cfg(unix)]
+    pub fn enable() -> Option<Self> {
+        if !io::stdin().is_terminal() {
+            return None;
+        }
+        // SAFETY: `termios` is plain data filled in by `tcgetattr`
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return None;
+        }
+        let original = termios;
+        termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+        termios.c_cc[libc::VMIN] = 1;
+        termios.c_cc[libc::VTIME] = 0;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) } != 0 {
+            return None;
+        }
+        Some(EditingMode { original })
+    }
+
+    #[cfg(not(unix))]
+    pub fn enable() -> Option<Self> {
+        None
+    }
+}
+
+impl Drop for EditingMode {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+// Reads lines from stdin with tab-completion and a history browsed with the
+// arrow keys. Without `EditingMode` enabled, lines are read as they are.
+pub struct LineEditor<C> {
+    editing: bool,
+    history: Vec<String>,
+    // The possible completions of a line, as whole lines
+    complete: C,
+}
+
+impl<C: Fn(&str) -> Vec<String>> LineEditor<C> {
+    pub fn new(editing: bool, complete: C) -> Self {
+        LineEditor {
+            editing,
+            history: Vec::new(),
+            complete,
+        }
+    }
+
+    // Read the next line, or None once the input ended or on ctrl-c and ctrl-d
+    pub fn read_line(&mut self) -> io::Result<Option<String>> {
+        if !self.editing {
+            let mut input = String::new();
+            return match io::stdin().read_line(&mut input)? {
+                0 => Ok(None),
+                _ => Ok(Some(input)),
+            };
+        }
+
+        let mut stdin = io::stdin().lock();
+        let mut line = String::new();
+        // The bytes of a character that was only partially read so far
+        let mut partial = Vec::new();
+        // The entry of the history being shown, the end for a new line
+        let mut browsing = self.history.len();
+        loop {
+            let byte = match read_byte(&mut stdin)? {
+                Some(byte) => byte,
+                None => return Ok(None),
+            };
+            match byte {
+                b'\r' | b'\n' => {
+                    println!();
+                    if !line.trim().is_empty() && self.history.last() != Some(&line) {
+                        self.history.push(line.clone());
+                    }
+                    return Ok(Some(line));
+                }
+                // ctrl-c, or ctrl-d on an empty line
+                3 => {
+                    println!();
+                    return Ok(None);
+                }
+                4 if line.is_empty() => {
+                    println!();
+                    return Ok(None);
+                }
+                b'\t' => self.complete(&mut line)?,
+                // backspace
+                8 | 0x7f => {
+                    line.pop();
+                }
+                // escape sequences, of which only up and down are used
+                0x1b => {
+                    if read_byte(&mut stdin)? != Some(b'[') {
+                        continue;
+                    }
+                    match read_byte(&mut stdin)? {
+                        Some(b'A') if browsing > 0 => {
+                            browsing -= 1;
+                            line = self.history[browsing].clone();
+                        }
+                        Some(b'B') if browsing < self.history.len() => {
+                            browsing += 1;
+                            line = self.history.get(browsing).cloned().unwrap_or_default();
+                        }
+                        // like delete, `ESC [ 3 ~`
+                        Some(b'0'..=b'9') => {
+                            while let Some(b'0'..=b'9' | b';') = read_byte(&mut stdin)? {}
+                        }
+                        _ => {}
+                    }
+                }
+                byte if byte < 0x20 => {}
+                byte => {
+                    partial.push(byte);
+                    if let Ok(c) = std::str::from_utf8(&partial) {
+                        line.push_str(c);
+                        partial.clear();
+                    } else if partial.len() >= 4 {
+                        partial.clear();
+                    }
+                }
+            }
+            redraw(&line)?;
+        }
+    }
+
+    // Complete the line, listing the completions if that doesn't get any further
+    fn complete(&self, line: &mut String) -> io::Result<()> {
+        let completions = (self.complete)(line);
+        let words = complete_line(line, &completions);
+        if !words.is_empty() {
+            println!();
+            println!("{}", words.join("  "));
+        }
+        Ok(())
+    }
+}
+
+// Complete the line as far as all of its completions agree. Returns the last
+// words of the completions to choose from if that doesn't get any further.
+pub fn complete_line<'a>(line: &mut String, completions: &'a [String]) -> Vec<&'a str> {
+    let prefix = match common_prefix(completions) {
+        Some(prefix) => prefix,
+        None => return Vec::new(),
+    };
+    if prefix.len() > line.len() {
+        *line = prefix.to_string();
+        Vec::new()
+    } else if completions.len() > 1 {
+        completions
+            .iter()
+            .filter_map(|completion| completion.split_whitespace().last())
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+fn read_byte(input: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+    match input.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+// Show the line again after it was edited
+fn redraw(line: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\r\x1B[K{line}")?;
+    stdout.flush()
+}
+
+// The longest prefix shared by all of the strings, None if there are none
+fn common_prefix(strings: &[String]) -> Option<&str> {
+    let first = strings.first()?;
+    let len = strings[1..].iter().fold(first.len(), |len, string| {
+        first[..len]
+            .char_indices()
+            .zip(string.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(string.len()), |((i, _), _)| i)
+    });
+    Some(&first[..len])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_common_prefix() {
+        let strings = |strings: &[&str]| strings.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(common_prefix(&[]), None);
+        assert_eq!(common_prefix(&strings(&["goto "])), Some("goto "));
+        assert_eq!(common_prefix(&strings(&["goto move1", "goto move2"])), Some("goto move"));
+        assert_eq!(common_prefix(&strings(&["goto move1", "goto m"])), Some("goto m"));
+        assert_eq!(common_prefix(&strings(&["next", "hint"])), Some(""));
+    }
+
+    #[test]
+    fn test_complete_line() {
+        let completions = vec!["goto move1".to_string(), "goto move2".to_string()];
+        let mut line = "goto m".to_string();
+        assert!(complete_line(&mut line, &completions).is_empty());
+        assert_eq!(line, "goto move");
+        assert_eq!(complete_line(&mut line, &completions), ["move1", "move2"]);
+        assert_eq!(line, "goto move");
+    }
+}
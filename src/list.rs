@@ -0,0 +1,82 @@
+use crate::exercise::Exercise;
+use crate::progress::ProgressStore;
+use argh::FromArgs;
+use std::io::Write;
+
+#[derive(FromArgs, PartialEq, Debug, Default)]
+#[argh(subcommand, name = "list")]
+/// Lists the exercises available in Rustlings
+pub struct ListArgs {
+    #[argh(switch, short = 'p')]
+    /// show only the paths of the exercises
+    paths: bool,
+    #[argh(switch, short = 'n')]
+    /// show only the names of the exercises
+    names: bool,
+    #[argh(option, short = 'f')]
+    /// provide a string to match exercise names
+    /// comma separated patterns are acceptable
+    filter: Option<String>,
+    #[argh(switch, short = 'u')]
+    /// display only exercises not yet solved
+    unsolved: bool,
+    #[argh(switch, short = 's')]
+    /// display only exercises that have been solved
+    solved: bool,
+}
+
+// Print the exercises matching the arguments along with their status,
+// followed by the overall progress
+pub fn list(exercises: &[Exercise], store: &ProgressStore, subargs: &ListArgs) -> std::io::Result<()> {
+    write_list(&mut std::io::stdout().lock(), exercises, store, subargs)
+}
+
+// Write the list to `handle` instead, like the output pane of watch mode
+pub fn write_list(
+    handle: &mut impl Write,
+    exercises: &[Exercise],
+    store: &ProgressStore,
+    subargs: &ListArgs,
+) -> std::io::Result<()> {
+    if !subargs.paths && !subargs.names {
+        writeln!(handle, "{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status")?;
+    }
+    let mut exercises_done: u16 = 0;
+    let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
+    for e in exercises {
+        let fname = format!("{}", e.path.display());
+        let filter_cond = filters
+            .split(',')
+            .filter(|f| !f.trim().is_empty())
+            .any(|f| e.name.contains(f) || fname.contains(f));
+        let done = store.is_done(e);
+        let status = if done {
+            exercises_done += 1;
+            "Done"
+        } else {
+            "Pending"
+        };
+        let solve_cond = {
+            (done && subargs.solved)
+                || (!done && subargs.unsolved)
+                || (!subargs.solved && !subargs.unsolved)
+        };
+        if solve_cond && (filter_cond || subargs.filter.is_none()) {
+            if subargs.paths {
+                writeln!(handle, "{fname}")?;
+            } else if subargs.names {
+                writeln!(handle, "{}", e.name)?;
+            } else {
+                writeln!(handle, "{:<17}\t{fname:<46}\t{status:<7}", e.name)?;
+            }
+        }
+    }
+    let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
+    writeln!(
+        handle,
+        "Progress: You completed {} / {} exercises ({:.1} %).",
+        exercises_done,
+        exercises.len(),
+        percentage_progress
+    )
+}
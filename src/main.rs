@@ -1,31 +1,54 @@
-use crate::exercise::{Exercise, ExerciseList};
+use crate::cache::CACHE_DIR;
+use crate::exercise::{Exercise, Limits, Mode, RunOptions, DEFAULT_OUTPUT_LIMIT, DEFAULT_TIMEOUT};
+use crate::grade::{default_jobs, grade};
+use crate::integrity::tests_fingerprint;
+use crate::list::{list, ListArgs};
+use crate::progress::ProgressStore;
 use crate::project::RustAnalyzerProject;
-use crate::run::{reset, run};
+use crate::report::{write_junit, write_report, Format};
+use crate::run::run;
+use crate::solution::{check_solutions, print_solution};
+use crate::update::{update, Curriculum};
+use crate::track::TRACKS_DIR;
+use crate::validate::Severity;
 use crate::verify::verify;
+use crate::watch::{watch, WatchStatus};
+use crate::workspace::{VENDOR_DIR, WORKSPACE_DIR};
 use argh::FromArgs;
 use console::Emoji;
-use notify::DebouncedEvent;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
-use serde::{Deserialize, Serialize};
-use std::ffi::OsStr;
 use std::fs;
-use std::io::{self, prelude::*};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::{channel, RecvTimeoutError};
-use std::sync::{Arc, Mutex};
-use std::thread;
 use std::time::Duration;
-use std::time::{UNIX_EPOCH, SystemTime};
 
 #[macro_use]
 mod ui;
 
+mod cache;
+mod diagnostics;
+mod embedded;
 mod exercise;
+mod explain;
+mod grade;
+mod integrity;
+mod junit;
+mod line_editor;
+mod list;
+mod merge;
+mod progress;
+mod pristine;
 mod project;
+mod report;
 mod run;
+mod solution;
+mod track;
+mod tui;
+mod update;
+mod validate;
 mod verify;
+mod watch;
+mod watcher;
+mod workspace;
 
 // In sync with crate version
 const VERSION: &str = "5.5.1";
@@ -39,6 +62,23 @@ struct Args {
     /// show the executable version
     #[argh(switch, short = 'v')]
     version: bool,
+    /// the number of seconds an exercise may run for, unless it sets its own timeout (defaults to 10)
+    #[argh(option)]
+    timeout: Option<u64>,
+    /// the maximum memory in MiB an exercise may use (only enforced on Unix)
+    #[argh(option)]
+    memory_limit: Option<u64>,
+    /// the maximum output in KiB an exercise may write to stdout or stderr (defaults to 1024)
+    #[argh(option)]
+    output_limit: Option<usize>,
+    /// compile and run every exercise again, instead of reporting the results
+    /// cached for exercises that didn't change
+    #[argh(switch)]
+    no_cache: bool,
+    /// the track of exercises to work on, listed in tracks/<track>.toml
+    /// (defaults to the exercises in info.toml)
+    #[argh(option)]
+    track: Option<String>,
     #[argh(subcommand)]
     nested: Option<Subcommands>,
 }
@@ -50,20 +90,104 @@ enum Subcommands {
     Watch(WatchArgs),
     Run(RunArgs),
     Reset(ResetArgs),
+    RestoreBackup(RestoreBackupArgs),
     Hint(HintArgs),
     List(ListArgs),
     Lsp(LspArgs),
-    CicvVerify(CicvVerifyArgs)
+    CicvVerify(CicvVerifyArgs),
+    Fingerprint(FingerprintArgs),
+    Solution(SolutionArgs),
+    CheckSolutions(CheckSolutionsArgs),
+    Vendor(VendorArgs),
+    Init(InitArgs),
+    Update(UpdateArgs),
+    CheckInfo(CheckInfoArgs),
 }
 
 #[derive(FromArgs, PartialEq, Debug)]
-#[argh(subcommand, name = "cicvverify", description = "cicvverify")]
-struct CicvVerifyArgs {}
+#[argh(subcommand, name = "cicvverify")]
+/// Grades all exercises in parallel and writes the results to a file
+struct CicvVerifyArgs {
+    #[argh(option, short = 'j')]
+    /// the number of exercises graded at the same time
+    /// (defaults to the number of CPUs)
+    jobs: Option<usize>,
+    #[argh(option, short = 'o')]
+    /// the file the results are written to
+    /// (defaults to .github/result/check_result.<extension of the format>)
+    output: Option<PathBuf>,
+    #[argh(option, short = 'f', default = "Format::Json")]
+    /// the format of the results: json (default), junit, tap or markdown
+    format: Format,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "fingerprint")]
+/// Prints the fingerprints of the exercises' tests for use in info.toml
+struct FingerprintArgs {
+    #[argh(positional)]
+    /// the name of the exercise, all test exercises if not given
+    name: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "solution")]
+/// Shows the reference solution of an exercise you've done
+struct SolutionArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: String,
+    #[argh(switch, short = 'd')]
+    /// show how your exercise differs from the solution
+    diff: bool,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-solutions")]
+/// Grades the reference solutions to check that every exercise can be solved
+struct CheckSolutionsArgs {
+    #[argh(option, short = 'j')]
+    /// the number of solutions graded at the same time
+    /// (defaults to the number of CPUs)
+    jobs: Option<usize>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "check-info")]
+/// Checks info.toml for mistakes, like exercises without a file or files without an exercise
+struct CheckInfoArgs {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "init")]
+/// Creates a fresh set of exercises from the ones built into rustlings
+struct InitArgs {
+    #[argh(positional, default = "PathBuf::from(\"rustlings\")")]
+    /// the directory to create the exercises in (defaults to rustlings)
+    dir: PathBuf,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "update")]
+/// Updates the exercises to the ones built into rustlings, keeping your changes
+struct UpdateArgs {
+    #[argh(option)]
+    /// update to the exercises in another rustlings directory instead
+    from: Option<PathBuf>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "vendor")]
+/// Downloads the crates the exercises use into vendor/ for building offline
+struct VendorArgs {}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "verify")]
 /// Verifies all exercises according to the recommended order
-struct VerifyArgs {}
+struct VerifyArgs {
+    #[argh(option)]
+    /// write the results as JUnit XML to the given file
+    junit: Option<PathBuf>,
+}
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "watch")]
@@ -85,8 +209,23 @@ struct RunArgs {
 
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand, name = "reset")]
-/// Resets a single exercise using "git stash -- <filename>"
+/// Resets exercises to their original versions, backing up yours
 struct ResetArgs {
+    #[argh(positional)]
+    /// the name of the exercise
+    name: Option<String>,
+    #[argh(switch)]
+    /// reset all exercises
+    all: bool,
+    #[argh(option)]
+    /// reset the exercises in the given directory, like `move_semantics`
+    category: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "restore-backup")]
+/// Restores the version of an exercise backed up when it was last reset
+struct RestoreBackupArgs {
     #[argh(positional)]
     /// the name of the exercise
     name: String,
@@ -106,49 +245,6 @@ struct HintArgs {
 /// Enable rust-analyzer for exercises
 struct LspArgs {}
 
-#[derive(FromArgs, PartialEq, Debug)]
-#[argh(subcommand, name = "list")]
-/// Lists the exercises available in Rustlings
-struct ListArgs {
-    #[argh(switch, short = 'p')]
-    /// show only the paths of the exercises
-    paths: bool,
-    #[argh(switch, short = 'n')]
-    /// show only the names of the exercises
-    names: bool,
-    #[argh(option, short = 'f')]
-    /// provide a string to match exercise names
-    /// comma separated patterns are acceptable
-    filter: Option<String>,
-    #[argh(switch, short = 'u')]
-    /// display only exercises not yet solved
-    unsolved: bool,
-    #[argh(switch, short = 's')]
-    /// display only exercises that have been solved
-    solved: bool,
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseCheckList {
-    pub exercises: Vec<ExerciseResult>,
-    pub user_name: Option<String>,
-    pub statistics: ExerciseStatistics
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct ExerciseResult {
-    pub name: String,
-    pub result: bool
-}
-
-#[derive(Deserialize, Serialize)]
-pub struct  ExerciseStatistics {
-    pub total_exercations: usize,
-    pub total_succeeds: usize,
-    pub total_failures: usize,
-    pub total_time: u32,
-}
-
 #[tokio::main]
 async fn main() {
     let args: Args = argh::from_env();
@@ -162,12 +258,28 @@ async fn main() {
         println!("\n{WELCOME}\n");
     }
 
+    // Creating the exercises is the one thing that works without them
+    if let Some(Subcommands::Init(subargs)) = &args.nested {
+        let dir = subargs.dir.display();
+        match embedded::init(&subargs.dir) {
+            Ok(()) => {
+                success!("Created the exercises in {}", dir);
+                println!("Run `cd {dir}` and `rustlings watch` to get started!");
+            }
+            Err(e) => {
+                println!("Failed to create the exercises in {dir}: {e}");
+                std::process::exit(1);
+            }
+        }
+        std::process::exit(0);
+    }
+
     if !Path::new("info.toml").exists() {
         println!(
             "{} must be run from the rustlings directory",
             std::env::current_exe().unwrap().to_str().unwrap()
         );
-        println!("Try `cd rustlings/`!");
+        println!("Try `cd rustlings/`, or create the exercises with `rustlings init`!");
         std::process::exit(1);
     }
 
@@ -178,159 +290,263 @@ async fn main() {
         std::process::exit(1);
     }
 
-    let toml_str = &fs::read_to_string("info.toml").unwrap();
-    let exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
+    if let Some(Subcommands::CheckInfo(_subargs)) = &args.nested {
+        let clean = check_info();
+        std::process::exit(if clean { 0 } else { 1 });
+    }
+    let track = args.track.as_deref();
+    if let Some(track) = track.filter(|track| !track::is_name(track)) {
+        println!("`{track}` isn't the name of a track, which can't contain `/`, `\\` or `..`");
+        std::process::exit(1);
+    }
+    let manifests = track::load(track).unwrap_or_else(|problem| {
+        println!("{problem}");
+        if let Some(track) = track.filter(|_| !track::manifest_path(track).exists()) {
+            let tracks = track::tracks(Path::new(TRACKS_DIR));
+            println!("There's no track named {track}, the tracks are: {}", tracks.join(", "));
+        }
+        std::process::exit(1);
+    });
+    // Resetting and updating exercises repairs missing files
+    let repairs = matches!(
+        &args.nested,
+        Some(Subcommands::Reset(_) | Subcommands::RestoreBackup(_) | Subcommands::Update(_))
+    );
+    let errors = validate::check(&manifests, Path::new("."));
+    if !errors.is_empty() && !repairs {
+        for problem in errors {
+            println!("{problem}");
+        }
+        println!("Run `rustlings check-info` to see all problems with the exercises");
+        std::process::exit(1);
+    }
+    let exercise_list = track::combine(manifests);
+    let rubric = exercise_list.categories;
+    let backend = exercise_list.backend;
+    let exercises = exercise_list.exercises;
     let verbose = args.nocapture;
 
+    let options = RunOptions {
+        limits: Limits {
+            timeout: args.timeout.map_or(DEFAULT_TIMEOUT, Duration::from_secs),
+            memory: args.memory_limit.map(|mib| mib * 1024 * 1024),
+            output: Some(args.output_limit.map_or(DEFAULT_OUTPUT_LIMIT, |kib| kib * 1024)),
+        },
+        cancel: Default::default(),
+        backend,
+        cache: (!args.no_cache).then(|| PathBuf::from(CACHE_DIR)),
+    };
+    // Only the commands that compile exercises need the workspace
+    let compiles = matches!(
+        &args.nested,
+        Some(
+            Subcommands::Verify(_)
+                | Subcommands::Watch(_)
+                | Subcommands::Run(_)
+                | Subcommands::CicvVerify(_)
+                | Subcommands::CheckSolutions(_)
+        )
+    );
+    if compiles && exercises.iter().any(|e| e.uses_workspace(backend)) {
+        if let Err(e) = workspace::generate(&exercises, backend) {
+            println!("Failed to generate the workspace in {WORKSPACE_DIR}: {e}");
+            std::process::exit(1);
+        }
+    }
+    let mut store = ProgressStore::load(track);
+
     let command = args.nested.unwrap_or_else(|| {
         println!("{DEFAULT_OUT}\n");
         std::process::exit(0);
     });
     match command {
         Subcommands::List(subargs) => {
-            if !subargs.paths && !subargs.names {
-                println!("{:<17}\t{:<46}\t{:<7}", "Name", "Path", "Status");
+            // Somehow using println! leads to the binary panicking
+            // when its output is piped.
+            // So, we're handling a Broken Pipe error and exiting with 0 anyway
+            match list(&exercises, &store, &subargs) {
+                Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => std::process::exit(1),
+                _ => std::process::exit(0),
             }
-            let mut exercises_done: u16 = 0;
-            let filters = subargs.filter.clone().unwrap_or_default().to_lowercase();
-            exercises.iter().for_each(|e| {
-                let fname = format!("{}", e.path.display());
-                let filter_cond = filters
-                    .split(',')
-                    .filter(|f| !f.trim().is_empty())
-                    .any(|f| e.name.contains(&f) || fname.contains(&f));
-                let status = if e.looks_done() {
-                    exercises_done += 1;
-                    "Done"
-                } else {
-                    "Pending"
-                };
-                let solve_cond = {
-                    (e.looks_done() && subargs.solved)
-                        || (!e.looks_done() && subargs.unsolved)
-                        || (!subargs.solved && !subargs.unsolved)
-                };
-                if solve_cond && (filter_cond || subargs.filter.is_none()) {
-                    let line = if subargs.paths {
-                        format!("{fname}\n")
-                    } else if subargs.names {
-                        format!("{}\n", e.name)
-                    } else {
-                        format!("{:<17}\t{fname:<46}\t{status:<7}\n", e.name)
-                    };
-                    // Somehow using println! leads to the binary panicking
-                    // when its output is piped.
-                    // So, we're handling a Broken Pipe error and exiting with 0 anyway
-                    let stdout = std::io::stdout();
-                    {
-                        let mut handle = stdout.lock();
-                        handle.write_all(line.as_bytes()).unwrap_or_else(|e| {
-                            match e.kind() {
-                                std::io::ErrorKind::BrokenPipe => std::process::exit(0),
-                                _ => std::process::exit(1),
-                            };
-                        });
-                    }
-                }
-            });
-            let percentage_progress = exercises_done as f32 / exercises.len() as f32 * 100.0;
-            println!(
-                "Progress: You completed {} / {} exercises ({:.1} %).",
-                exercises_done,
-                exercises.len(),
-                percentage_progress
-            );
-            std::process::exit(0);
         }
 
         Subcommands::Run(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
-            run(exercise, verbose).unwrap_or_else(|_| std::process::exit(1));
+            let exercise = find_exercise(&subargs.name, &exercises, &store);
+            run(exercise, &options, verbose).unwrap_or_else(|_| std::process::exit(1));
+            if exercise.looks_done() {
+                store.record_done(exercise);
+            }
         }
 
         Subcommands::Reset(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let selected: Vec<&Exercise> = match (&subargs.name, &subargs.category) {
+                (Some(name), None) if !subargs.all => vec![find_exercise(name, &exercises, &store)],
+                (None, Some(category)) if !subargs.all => exercises
+                    .iter()
+                    .filter(|e| e.path.parent().is_some_and(|dir| dir.ends_with(category)))
+                    .collect(),
+                (None, None) if subargs.all => exercises.iter().collect(),
+                _ => {
+                    println!("Reset either an exercise by its name, a --category or --all of them");
+                    std::process::exit(1);
+                }
+            };
+            if selected.is_empty() {
+                println!("There are no exercises to reset");
+                std::process::exit(1);
+            }
+            let single = selected.len() == 1;
+            let mut failed = false;
+            for exercise in selected {
+                match pristine::reset(exercise) {
+                    Ok(Some(backup)) => {
+                        success!("Reset {}, your version is backed up in {}", exercise, backup.display())
+                    }
+                    Ok(None) if single => println!("{exercise} is already in its original state"),
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!("{}", e);
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
 
-            reset(exercise).unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::RestoreBackup(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises, &store);
+            match pristine::restore_backup(exercise) {
+                Ok(backup) => success!("Restored {} from {}", exercise, backup.display()),
+                Err(e) => {
+                    warn!("{}", e);
+                    std::process::exit(1);
+                }
+            }
         }
 
         Subcommands::Hint(subargs) => {
-            let exercise = find_exercise(&subargs.name, &exercises);
+            let exercise = find_exercise(&subargs.name, &exercises, &store);
 
             println!("{}", exercise.hint);
         }
 
-        Subcommands::Verify(_subargs) => {
-            verify(&exercises, (0, exercises.len()), verbose, false)
-                .unwrap_or_else(|_| std::process::exit(1));
+        Subcommands::Verify(subargs) => {
+            let mut results = Vec::new();
+            let verified = verify(
+                &exercises,
+                (0, exercises.len()),
+                &options,
+                verbose,
+                false,
+                &mut store,
+                Some(&mut results),
+            );
+            if let Some(path) = subargs.junit {
+                let skipped: Vec<&Exercise> = exercises.iter().skip(results.len()).collect();
+                if let Err(e) = write_junit(&results, &skipped, &path) {
+                    println!("Failed to write the results to {}: {e}", path.display());
+                    std::process::exit(1);
+                }
+            }
+            verified.unwrap_or_else(|_| std::process::exit(1));
         }
 
-        Subcommands::CicvVerify(_subargs) => {
-            // let toml_str = &fs::read_to_string("info.toml").unwrap();
-            // exercises = toml::from_str::<ExerciseList>(toml_str).unwrap().exercises;
-            let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let rights = Arc::new(Mutex::new(0));
-            let alls = exercises.len();
-
-            let exercise_check_list =  Arc::new(Mutex::new(
-                ExerciseCheckList {
-                    exercises: vec![], 
-                    user_name:  None, 
-                    statistics: ExerciseStatistics { 
-                        total_exercations: alls, 
-                        total_succeeds: 0, 
-                        total_failures: 0, 
-                        total_time: 0, 
-                    }
-                }
-            ));
-
-            let mut tasks = vec![];
-            for exercise in exercises {
-                let now_start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                let inner_exercise = exercise;
-                let c_mutex = Arc::clone(&rights);
-                let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-                let _verbose = verbose.clone();
-                let t = tokio::task::spawn( async move {
-                    match run(&inner_exercise, true) {
-                    // match verify(vec![&inner_exercise], (0, 1), true, true) {
-                        Ok(_) => {
-                            *c_mutex.lock().unwrap() += 1;
-                            println!("{}执行成功", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: true,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_succeeds += 1;
-                        },
-                        Err(_) => {
-                            println!("{}执行失败", inner_exercise.name);
-                            println!("总的题目数: {}", alls);
-                            println!("当前做正确的题目数: {}", *c_mutex.lock().unwrap());
-                            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-                            println!("当前修改试卷耗时: {} s", now_end - now_start);
-                            exercise_check_list_ref.lock().unwrap().exercises.push(ExerciseResult{ 
-                                name: inner_exercise.name, result: false,
-                            });
-                            exercise_check_list_ref.lock().unwrap().statistics.total_failures += 1;
-                        }
-                    }
+        Subcommands::CicvVerify(subargs) => {
+            let jobs = subargs.jobs.unwrap_or_else(default_jobs);
+            let exercise_check_list = grade(exercises, &rubric, jobs, &options).await;
+            let output = subargs
+                .output
+                .unwrap_or_else(|| subargs.format.default_output());
+            if let Err(e) = write_report(&exercise_check_list, &output, subargs.format) {
+                println!("Failed to write the results to {}: {e}", output.display());
+                std::process::exit(1);
+            }
+        }
+
+        Subcommands::Fingerprint(subargs) => {
+            let selected: Vec<&Exercise> = match &subargs.name {
+                Some(name) => vec![find_exercise(name, &exercises, &store)],
+                None => exercises.iter().filter(|e| matches!(e.mode, Mode::Test)).collect(),
+            };
+            for exercise in selected {
+                let source = fs::read_to_string(&exercise.path).unwrap_or_else(|e| {
+                    println!("Failed to read {exercise}: {e}");
+                    std::process::exit(1);
                 });
-                tasks.push(t);
+                println!("{}", exercise.name);
+                println!("tests_fingerprint = \"{}\"", tests_fingerprint(&source));
             }
-            for task in tasks { task.await.unwrap(); }
-            let now_end = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
-            let total_time = now_end - now_start;
-            println!("===============================试卷批改完成,总耗时: {} s; ==================================", total_time);
-            let exercise_check_list_ref = Arc::clone(&exercise_check_list);
-            exercise_check_list_ref.lock().unwrap().statistics.total_time = total_time as u32;
-            let serialized = serde_json::to_string_pretty(&*exercise_check_list.lock().unwrap()).unwrap();
-            fs::write(".github/result/check_result.json", serialized).unwrap();
-        },
+        }
+
+        Subcommands::Solution(subargs) => {
+            let exercise = find_exercise(&subargs.name, &exercises, &store);
+            if !store.is_done(exercise) {
+                println!("Solve {} first to see its solution!", exercise.name);
+                std::process::exit(1);
+            }
+            print_solution(exercise, subargs.diff).unwrap_or_else(|_| std::process::exit(1));
+        }
+
+        Subcommands::CheckSolutions(subargs) => {
+            let jobs = subargs.jobs.unwrap_or_else(default_jobs);
+            if !check_solutions(&exercises, jobs, &options).await {
+                std::process::exit(1);
+            }
+        }
+
+        // Handled before looking for info.toml
+        Subcommands::Init(_subargs) => {}
+
+        // Handled right after reading info.toml
+        Subcommands::CheckInfo(_subargs) => {}
+
+        Subcommands::Update(subargs) => {
+            let curriculum = match &subargs.from {
+                Some(dir) => Curriculum::from_dir(dir).unwrap_or_else(|e| {
+                    println!("{e}");
+                    std::process::exit(1);
+                }),
+                None => Curriculum::embedded(),
+            };
+            match update(&curriculum) {
+                Ok(true) => success!("{}", "Updated the exercises"),
+                Ok(false) => {
+                    println!("Updated the exercises, resolve the conflicts before going on");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    warn!("{}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+
+        Subcommands::Vendor(_subargs) => {
+            if let Err(e) = workspace::generate(&exercises, backend) {
+                println!("Failed to generate the workspace in {WORKSPACE_DIR}: {e}");
+                std::process::exit(1);
+            }
+            let vendored = Command::new("cargo")
+                .arg("vendor")
+                .arg("--manifest-path")
+                .arg(Path::new(WORKSPACE_DIR).join("Cargo.toml"))
+                .arg(VENDOR_DIR)
+                .stdout(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success());
+            if !vendored {
+                println!("Failed to vendor the crates of the exercises");
+                std::process::exit(1);
+            }
+            // Point the workspace at the vendored crates
+            if let Err(e) = workspace::generate(&exercises, backend) {
+                println!("Failed to generate the workspace in {WORKSPACE_DIR}: {e}");
+                std::process::exit(1);
+            }
+            println!("The crates of the exercises are in {VENDOR_DIR}/, exercises are now built offline");
+        }
 
         Subcommands::Lsp(_subargs) => {
             let mut project = RustAnalyzerProject::new();
@@ -351,81 +567,83 @@ async fn main() {
             }
         }
 
-        Subcommands::Watch(_subargs) => match watch(&exercises, verbose, _subargs.success_hints) {
-            Err(e) => {
-                println!(
-                    "Error: Could not watch your progress. Error message was {:?}.",
-                    e
-                );
-                println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
-                std::process::exit(1);
-            }
-            Ok(WatchStatus::Finished) => {
-                println!(
-                    "{emoji} All exercises completed! {emoji}",
-                    emoji = Emoji("🎉", "★")
-                );
-                println!("\n{FENISH_LINE}\n");
-            }
-            Ok(WatchStatus::Unfinished) => {
-                println!("We hope you're enjoying learning about Rust!");
-                println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+        Subcommands::Watch(subargs) => {
+            // Saving an exercise again cancels checking the old version
+            match watch(&exercises, &mut store, &options, verbose, subargs.success_hints) {
+                Err(e) => {
+                    println!(
+                        "Error: Could not watch your progress. Error message was {:?}.",
+                        e
+                    );
+                    println!("Most likely you've run out of disk space or your 'inotify limit' has been reached.");
+                    std::process::exit(1);
+                }
+                Ok(WatchStatus::Finished) => {
+                    println!(
+                        "{emoji} All exercises completed! {emoji}",
+                        emoji = Emoji("🎉", "★")
+                    );
+                    println!("\n{FENISH_LINE}\n");
+                }
+                Ok(WatchStatus::Unfinished) => {
+                    println!("We hope you're enjoying learning about Rust!");
+                    println!("If you want to continue working on the exercises at a later point, you can simply run `rustlings watch` again");
+                }
             }
-        },
+        }
     }
 }
 
-fn spawn_watch_shell(
-    failed_exercise_hint: &Arc<Mutex<Option<String>>>,
-    should_quit: Arc<AtomicBool>,
-) {
-    let failed_exercise_hint = Arc::clone(failed_exercise_hint);
-    println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
-    thread::spawn(move || loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(_) => {
-                let input = input.trim();
-                if input == "hint" {
-                    if let Some(hint) = &*failed_exercise_hint.lock().unwrap() {
-                        println!("{hint}");
-                    }
-                } else if input == "clear" {
-                    println!("\x1B[2J\x1B[1;1H");
-                } else if input.eq("quit") {
-                    should_quit.store(true, Ordering::SeqCst);
-                    println!("Bye!");
-                } else if input.eq("help") {
-                    println!("Commands available to you in watch mode:");
-                    println!("  hint   - prints the current exercise's hint");
-                    println!("  clear  - clears the screen");
-                    println!("  quit   - quits watch mode");
-                    println!("  !<cmd> - executes a command, like `!rustc --explain E0381`");
-                    println!("  help   - displays this help message");
-                    println!();
-                    println!("Watch mode automatically re-evaluates the current exercise");
-                    println!("when you edit a file's contents.")
-                } else if let Some(cmd) = input.strip_prefix('!') {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.is_empty() {
-                        println!("no command provided");
-                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
-                        println!("failed to execute command `{}`: {}", cmd, e);
-                    }
-                } else {
-                    println!("unknown command: {input}");
-                }
+// Check info.toml and every track for problems, and warn about exercise
+// files none of them uses. Returns whether there were no errors.
+fn check_info() -> bool {
+    let root = Path::new(".");
+    let tracks = track::tracks(Path::new(TRACKS_DIR));
+    let mut problems = Vec::new();
+    let mut exercises = Vec::new();
+    for track in std::iter::once(None).chain(tracks.iter().map(|track| Some(track.as_str()))) {
+        let found = match track::load(track) {
+            Ok(manifests) => {
+                let mut found = validate::check(&manifests, root);
+                found.extend(manifests.iter().flat_map(validate::unknown_keys));
+                exercises.extend(manifests.into_iter().flat_map(|manifest| manifest.list.exercises));
+                found
+            }
+            Err(problem) => vec![problem],
+        };
+        // Tracks including the same manifest have the same problems with it
+        for problem in found {
+            if !problems.contains(&problem) {
+                problems.push(problem);
             }
-            Err(error) => println!("error reading command: {error}"),
         }
-    });
+    }
+    problems.extend(validate::unlisted(exercises.iter(), root));
+
+    for problem in &problems {
+        println!("{problem}");
+    }
+    let errors = problems.iter().filter(|problem| problem.severity == Severity::Error).count();
+    if errors > 0 {
+        println!("Found {errors} errors in info.toml and the tracks");
+        return false;
+    }
+    let mut names: Vec<&str> = exercises.iter().map(|exercise| exercise.name.as_str()).collect();
+    names.sort();
+    names.dedup();
+    success!(
+        "info.toml and {} tracks list {} exercises correctly",
+        tracks.len(),
+        names.len()
+    );
+    true
 }
 
-fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
+fn find_exercise<'a>(name: &str, exercises: &'a [Exercise], store: &ProgressStore) -> &'a Exercise {
     if name.eq("next") {
         exercises
             .iter()
-            .find(|e| !e.looks_done())
+            .find(|e| !store.is_done(e))
             .unwrap_or_else(|| {
                 println!("🎉 Congratulations! You have done all the exercises!");
                 println!("🔚 There are no more exercises to do next!");
@@ -442,89 +660,9 @@ fn find_exercise<'a>(name: &str, exercises: &'a [Exercise]) -> &'a Exercise {
     }
 }
 
-enum WatchStatus {
-    Finished,
-    Unfinished,
-}
-
-fn watch(
-    exercises: &[Exercise],
-    verbose: bool,
-    success_hints: bool,
-) -> notify::Result<WatchStatus> {
-    /* Clears the terminal with an ANSI escape code.
-    Works in UNIX and newer Windows terminals. */
-    fn clear_screen() {
-        println!("\x1Bc");
-    }
-
-    let (tx, rx) = channel();
-    let should_quit = Arc::new(AtomicBool::new(false));
-
-    let mut watcher: RecommendedWatcher = Watcher::new(tx, Duration::from_secs(1))?;
-    watcher.watch(Path::new("./exercises"), RecursiveMode::Recursive)?;
-
-    clear_screen();
-
-    let to_owned_hint = |t: &Exercise| t.hint.to_owned();
-    let failed_exercise_hint = match verify(
-        exercises.iter(),
-        (0, exercises.len()),
-        verbose,
-        success_hints,
-    ) {
-        Ok(_) => return Ok(WatchStatus::Finished),
-        Err(exercise) => Arc::new(Mutex::new(Some(to_owned_hint(exercise)))),
-    };
-    spawn_watch_shell(&failed_exercise_hint, Arc::clone(&should_quit));
-    loop {
-        match rx.recv_timeout(Duration::from_secs(1)) {
-            Ok(event) => match event {
-                DebouncedEvent::Create(b) | DebouncedEvent::Chmod(b) | DebouncedEvent::Write(b) => {
-                    if b.extension() == Some(OsStr::new("rs")) && b.exists() {
-                        let filepath = b.as_path().canonicalize().unwrap();
-                        let pending_exercises = exercises
-                            .iter()
-                            .find(|e| filepath.ends_with(&e.path))
-                            .into_iter()
-                            .chain(
-                                exercises
-                                    .iter()
-                                    .filter(|e| !e.looks_done() && !filepath.ends_with(&e.path)),
-                            );
-                        let num_done = exercises.iter().filter(|e| e.looks_done()).count();
-                        clear_screen();
-                        match verify(
-                            pending_exercises,
-                            (num_done, exercises.len()),
-                            verbose,
-                            success_hints,
-                        ) {
-                            Ok(_) => return Ok(WatchStatus::Finished),
-                            Err(exercise) => {
-                                let mut failed_exercise_hint = failed_exercise_hint.lock().unwrap();
-                                *failed_exercise_hint = Some(to_owned_hint(exercise));
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            },
-            Err(RecvTimeoutError::Timeout) => {
-                // the timeout expired, just check the `should_quit` variable below then loop again
-            }
-            Err(e) => println!("watch error: {e:?}"),
-        }
-        // Check if we need to exit
-        if should_quit.load(Ordering::SeqCst) {
-            return Ok(WatchStatus::Unfinished);
-        }
-    }
-}
-
 fn rustc_exists() -> bool {
     Command::new("rustc")
-        .args(&["--version"])
+        .args(["--version"])
         .stdout(Stdio::null())
         .spawn()
         .and_then(|mut child| child.wait())
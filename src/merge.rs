@@ -0,0 +1,127 @@
+use crate::solution::{diff_lines, DiffLine};
+
+// The result of merging two versions of a text changed from the same base
+pub struct Merged {
+    pub text: String,
+    // The number of places both versions changed differently, which are
+    // marked like git does
+    pub conflicts: usize,
+}
+
+// Merge the changes `yours` and `theirs` made to `base` line by line.
+// Lines changed in only one of them take that change, lines changed the
+// same way in both take it once, and anything else is a conflict showing both.
+pub fn merge(base: &str, yours: &str, theirs: &str) -> Merged {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let your_lines: Vec<&str> = yours.lines().collect();
+    let their_lines: Vec<&str> = theirs.lines().collect();
+    let in_yours = matches(base, yours);
+    let in_theirs = matches(base, theirs);
+
+    let mut merged = Merged {
+        text: String::new(),
+        conflicts: 0,
+    };
+    // The positions in the base, your lines and their lines merged so far
+    let (mut i, mut a, mut b) = (0, 0, 0);
+    loop {
+        // The next base line both versions kept
+        let next = (i..base_lines.len()).find_map(|k| Some((k, in_yours[k]?, in_theirs[k]?)));
+        let (k, ka, kb) = next.unwrap_or((base_lines.len(), your_lines.len(), their_lines.len()));
+        merged.add_chunk(&base_lines[i..k], &your_lines[a..ka], &their_lines[b..kb]);
+        if next.is_none() {
+            break;
+        }
+        merged.text.push_str(base_lines[k]);
+        merged.text.push('\n');
+        (i, a, b) = (k + 1, ka + 1, kb + 1);
+    }
+    merged
+}
+
+impl Merged {
+    // Add the lines between two lines kept by both versions
+    fn add_chunk(&mut self, base: &[&str], yours: &[&str], theirs: &[&str]) {
+        let lines = if yours == base || yours == theirs {
+            theirs
+        } else if theirs == base {
+            yours
+        } else {
+            self.conflicts += 1;
+            self.text.push_str("<<<<<<< your version\n");
+            self.add_lines(yours);
+            self.text.push_str("=======\n");
+            self.add_lines(theirs);
+            self.text.push_str(">>>>>>> updated version\n");
+            return;
+        };
+        self.add_lines(lines);
+    }
+
+    fn add_lines(&mut self, lines: &[&str]) {
+        for line in lines {
+            self.text.push_str(line);
+            self.text.push('\n');
+        }
+    }
+}
+
+// The number of lines removed from `base` or added to it in `other`
+pub fn changed_lines(base: &str, other: &str) -> usize {
+    diff_lines(base, other)
+        .iter()
+        .filter(|line| !matches!(line, DiffLine::Same(_)))
+        .count()
+}
+
+// The line of `other` every line of `base` is matched with, if any
+fn matches(base: &str, other: &str) -> Vec<Option<usize>> {
+    let mut matches = Vec::new();
+    let mut j = 0;
+    for line in diff_lines(base, other) {
+        match line {
+            DiffLine::Same(_) => {
+                matches.push(Some(j));
+                j += 1;
+            }
+            DiffLine::Removed(_) => matches.push(None),
+            DiffLine::Added(_) => j += 1,
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const BASE: &str = "fn main() {\n    // I AM NOT DONE\n    let x = 5;\n    assert_eq!(x, 5);\n}\n";
+
+    #[test]
+    fn test_merge_without_conflicts() {
+        let yours = "fn main() {\n    let x = 5;\n    assert_eq!(x, 5);\n}\n";
+        let theirs = "fn main() {\n    // I AM NOT DONE\n    let x = 5;\n    assert_eq!(x, 5, \"x\");\n}\n";
+        let merged = merge(BASE, yours, theirs);
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "fn main() {\n    let x = 5;\n    assert_eq!(x, 5, \"x\");\n}\n");
+
+        assert_eq!(merge(BASE, BASE, theirs).text, theirs);
+        assert_eq!(merge(BASE, yours, BASE).text, yours);
+        assert_eq!(merge(BASE, yours, yours).text, yours);
+    }
+
+    #[test]
+    fn test_merge_with_conflicts() {
+        let yours = "fn main() {\n    let x = 6;\n    assert_eq!(x, 5);\n}\n";
+        let theirs = "fn main() {\n    // I AM NOT DONE\n    let x = 7;\n    assert_eq!(x, 5);\n}\n";
+        let merged = merge(BASE, yours, theirs);
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "fn main() {\n\
+            <<<<<<< your version\n    let x = 6;\n\
+            =======\n    // I AM NOT DONE\n    let x = 7;\n\
+            >>>>>>> updated version\n    assert_eq!(x, 5);\n}\n"
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::embedded;
+use crate::exercise::Exercise;
+use crate::merge;
+use crate::progress;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// The exercises as they were shipped, kept by `init` and `update`
+pub const PRISTINE_DIR: &str = ".rustlings/pristine";
+
+// The versions of the exercises replaced by resetting or updating them, in a
+// directory for every exercise
+const BACKUP_DIR: &str = ".rustlings/backups";
+
+fn pristine_path(exercise: &Exercise) -> PathBuf {
+    Path::new(PRISTINE_DIR).join(&exercise.path)
+}
+
+// The original version of the exercise, if one was kept or the binary
+// was built with it. Both are shipped copies, never what's in the exercise
+// directory, which may have been worked on already.
+pub fn pristine(exercise: &Exercise) -> Option<String> {
+    kept_original(exercise).or_else(|| embedded::exercise(exercise))
+}
+
+// The original version of the exercise kept in this directory
+pub fn kept_original(exercise: &Exercise) -> Option<String> {
+    fs::read_to_string(pristine_path(exercise)).ok()
+}
+
+// The shipped version of the exercise your version most likely started from,
+// for directories that never kept one, like a clone of the repository.
+// That's the version you haven't changed if there is one, otherwise the one
+// you changed the fewest lines of.
+pub fn shipped_original(exercise: &Exercise, yours: &str) -> Option<String> {
+    let hash = progress::hash(yours);
+    let prior: Vec<(&str, String)> = embedded::prior_originals(&exercise.path).collect();
+    if let Some((_, original)) = prior.iter().find(|(shipped, _)| *shipped == hash) {
+        return Some(original.clone());
+    }
+    // The current version comes first to win ties, being the likelier one
+    // to have started from
+    embedded::exercise(exercise)
+        .into_iter()
+        .chain(prior.into_iter().map(|(_, contents)| contents))
+        .min_by_key(|contents| merge::changed_lines(contents, yours))
+}
+
+// Keep the contents as the original version of the exercise, like when
+// it was updated
+pub fn keep_original(exercise: &Exercise, contents: &str) -> io::Result<()> {
+    let copy = pristine_path(exercise);
+    if let Some(dir) = copy.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(copy, contents)
+}
+
+// Reset the exercise to its original version, backing up the current one.
+// Returns the backup, None if the exercise was original already.
+pub fn reset(exercise: &Exercise) -> Result<Option<PathBuf>, String> {
+    let original = pristine(exercise).ok_or_else(|| format!("No original version of {exercise} was kept"))?;
+    let current = fs::read_to_string(&exercise.path).unwrap_or_default();
+    if current == original {
+        return Ok(None);
+    }
+    let backup = backup(&exercise.name, &exercise.path, &current).map_err(|e| format!("Failed to back up {exercise}: {e}"))?;
+    fs::write(&exercise.path, original).map_err(|e| format!("Failed to reset {exercise}: {e}"))?;
+    Ok(Some(backup))
+}
+
+// Put back the latest backup of the exercise. The current version is backed
+// up first, so restoring again undoes it. Returns the backup restored.
+pub fn restore_backup(exercise: &Exercise) -> Result<PathBuf, String> {
+    let latest = backups(exercise)
+        .pop()
+        .ok_or_else(|| format!("There are no backups of {exercise}"))?;
+    let restored = fs::read_to_string(&latest).map_err(|e| format!("Failed to read {}: {e}", latest.display()))?;
+    let current = fs::read_to_string(&exercise.path).unwrap_or_default();
+    if current == restored {
+        return Ok(latest);
+    }
+    backup(&exercise.name, &exercise.path, &current).map_err(|e| format!("Failed to back up {exercise}: {e}"))?;
+    fs::write(&exercise.path, restored).map_err(|e| format!("Failed to restore {exercise}: {e}"))?;
+    Ok(latest)
+}
+
+// The backups of the exercise, oldest first
+pub fn backups(exercise: &Exercise) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(Path::new(BACKUP_DIR).join(&exercise.name))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    backups.sort();
+    backups
+}
+
+// Write a version of the exercise, or of another file, to a backup named
+// after the current time with the extension of its path
+pub fn backup(name: &str, path: &Path, contents: &str) -> io::Result<PathBuf> {
+    let dir = Path::new(BACKUP_DIR).join(name);
+    let extension = path.extension().map_or("rs".into(), |e| e.to_string_lossy());
+    fs::create_dir_all(&dir)?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let stamp = timestamp(secs);
+    // Resetting twice within a second mustn't overwrite the first backup
+    let mut path = dir.join(format!("{stamp}.{extension}"));
+    let mut n = 1;
+    while path.exists() {
+        path = dir.join(format!("{stamp}-{n}.{extension}"));
+        n += 1;
+    }
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+// The UTC time of the seconds since the Unix epoch, like `20261018-093005`,
+// which sorts in the order of time
+fn timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let (hour, minute, second) = (secs % 86400 / 3600, secs % 3600 / 60, secs % 60);
+    // Convert days to a date of the proleptic Gregorian calendar, after
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}{month:02}{day:02}-{hour:02}{minute:02}{second:02}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    #[test]
+    fn test_shipped_original() {
+        let intro2 = Exercise::for_test("intro2", "exercises/intro/intro2.rs", Mode::Compile);
+        let current = embedded::exercise(&intro2).unwrap();
+
+        let yours = current.replacen("fn main() {", "fn main() {\n    // my note", 1);
+        assert_eq!(shipped_original(&intro2, &yours).unwrap(), current);
+        // Solving the exercise never makes it the original
+        let solved = current.replace("// I AM NOT DONE\n", "").replace("Hello {}!", "Hello world!");
+        assert_eq!(shipped_original(&intro2, &solved).unwrap(), current);
+    }
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "19700101-000000");
+        assert_eq!(timestamp(951782400), "20000229-000000");
+        assert_eq!(timestamp(1792319405), "20261018-103005");
+    }
+}
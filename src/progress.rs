@@ -0,0 +1,134 @@
+use crate::exercise::Exercise;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// The file the progress is stored in, relative to the rustlings directory
+const STATE_FILE: &str = ".rustlings-state.json";
+
+// The file the progress on the track is stored in, every track keeps its own
+pub fn state_file(track: Option<&str>) -> PathBuf {
+    match track {
+        Some(track) => PathBuf::from(format!(".rustlings-state-{track}.json")),
+        None => PathBuf::from(STATE_FILE),
+    }
+}
+
+// The exercises that were verified to be done.
+// An exercise only counts as done once it compiled, passed its tests and
+// no longer had an `I AM NOT DONE` comment. The hash of its source at that
+// moment is stored along with it, so any later edit makes it pending again.
+#[derive(Deserialize, Serialize, Default, Debug)]
+pub struct ProgressStore {
+    // The hash of the source of every done exercise, by name
+    done: BTreeMap<String, String>,
+    // The state file the progress is saved to
+    #[serde(skip)]
+    file: PathBuf,
+}
+
+impl ProgressStore {
+    // Load the progress on the track from its state file.
+    // A missing or unreadable file means no exercise has been done yet.
+    pub fn load(track: Option<&str>) -> Self {
+        let file = state_file(track);
+        let store: ProgressStore = fs::read_to_string(&file)
+            .ok()
+            .and_then(|state| serde_json::from_str(&state).ok())
+            .unwrap_or_default();
+        ProgressStore { file, ..store }
+    }
+
+    // Whether the exercise was verified to be done and hasn't changed since
+    pub fn is_done(&self, exercise: &Exercise) -> bool {
+        match (self.done.get(&exercise.name), source_hash(&exercise.path)) {
+            (Some(done), Some(current)) => *done == current,
+            _ => false,
+        }
+    }
+
+    // Record the exercise as done with its current source
+    pub fn record_done(&mut self, exercise: &Exercise) {
+        let hash = match source_hash(&exercise.path) {
+            Some(hash) => hash,
+            None => return,
+        };
+        if self.done.get(&exercise.name) == Some(&hash) {
+            return;
+        }
+        self.done.insert(exercise.name.clone(), hash);
+        if let Err(e) = self.save() {
+            println!("Failed to save your progress to {}: {e}", self.file.display());
+        }
+    }
+
+    // Carry over the progress of an exercise that was renamed
+    pub fn rename(&mut self, from: &str, to: &str) -> io::Result<()> {
+        if self.done.contains_key(to) {
+            return Ok(());
+        }
+        match self.done.remove(from) {
+            Some(hash) => {
+                self.done.insert(to.to_string(), hash);
+                self.save()
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Write the progress to a temporary file first and move it in place,
+    // so that the state file is never left half written
+    fn save(&self) -> io::Result<()> {
+        let temp = self.file.with_extension("json.tmp");
+        let serialized = serde_json::to_string_pretty(self).expect("Failed to serialize to JSON");
+        fs::write(&temp, serialized + "\n")?;
+        fs::rename(&temp, &self.file)
+    }
+}
+
+// Hash the contents of a source file, ignoring the style of its line endings
+pub fn source_hash(path: &Path) -> Option<String> {
+    let source = fs::read_to_string(path).ok()?;
+    Some(hash(&source.replace("\r\n", "\n")))
+}
+
+// The SHA-256 of the text, as lowercase hex
+pub fn hash(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn test_edited_exercise_is_pending() {
+        // A directory of its own, so that parallel runs don't share the file
+        let dir = env::temp_dir().join(format!("rustlings_progress_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("progress_exercise.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+        let exercise = Exercise::for_test("progress_exercise", &path, Mode::Compile);
+        let mut store = ProgressStore::default();
+        assert!(!store.is_done(&exercise));
+
+        store.done.insert(exercise.name.clone(), source_hash(&path).unwrap());
+        assert!(store.is_done(&exercise));
+
+        fs::write(&path, "fn main() {}\r\n").unwrap();
+        assert!(store.is_done(&exercise));
+
+        fs::write(&path, "fn main() {\n    println!();\n}\n").unwrap();
+        assert!(!store.is_done(&exercise));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,170 @@
+use crate::exercise::Exercise;
+use crate::grade::{ExerciseCheckList, ExerciseResult};
+use crate::junit;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+// The directory the grading results are written to by default
+const DEFAULT_OUTPUT_DIR: &str = ".github/result";
+
+// The formats the grading results can be written in
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Format {
+    // The full report, as read by the GitHub classroom workflow
+    Json,
+    // JUnit XML, understood by most CI dashboards
+    Junit,
+    // The Test Anything Protocol
+    Tap,
+    // A human readable summary
+    Markdown,
+}
+
+impl Format {
+    // Where the results are written when no `--output` is given
+    pub fn default_output(&self) -> PathBuf {
+        let extension = match self {
+            Format::Json => "json",
+            Format::Junit => "xml",
+            Format::Tap => "tap",
+            Format::Markdown => "md",
+        };
+        Path::new(DEFAULT_OUTPUT_DIR).join(format!("check_result.{extension}"))
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "junit" => Ok(Format::Junit),
+            "tap" => Ok(Format::Tap),
+            "markdown" => Ok(Format::Markdown),
+            _ => Err(format!(
+                "unknown format '{s}', expected one of json, junit, tap or markdown"
+            )),
+        }
+    }
+}
+
+// Render the results in the given format and write them to `path`,
+// creating its parent directories if they don't exist yet
+pub fn write_report(list: &ExerciseCheckList, path: &Path, format: Format) -> io::Result<()> {
+    let contents = match format {
+        Format::Json => serde_json::to_string_pretty(list).expect("Failed to serialize to JSON"),
+        Format::Junit => junit::render(&list.exercises, &[]),
+        Format::Tap => tap(list),
+        Format::Markdown => markdown(list),
+    };
+    write_creating_dirs(path, contents)
+}
+
+// Write the verified exercises as JUnit XML to `path`, listing the ones
+// that weren't verified as skipped
+pub fn write_junit(results: &[ExerciseResult], skipped: &[&Exercise], path: &Path) -> io::Result<()> {
+    write_creating_dirs(path, junit::render(results, skipped))
+}
+
+fn write_creating_dirs(path: &Path, contents: String) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+fn tap(list: &ExerciseCheckList) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", list.exercises.len());
+    for (number, result) in list.exercises.iter().enumerate() {
+        let number = number + 1;
+        match result.failure {
+            None => {
+                let _ = writeln!(tap, "ok {number} - {}", result.name);
+            }
+            Some(failure) => {
+                let _ = writeln!(tap, "not ok {number} - {} # {failure}", result.name);
+                for line in output(result).lines() {
+                    let _ = writeln!(tap, "# {line}");
+                }
+            }
+        }
+    }
+    tap
+}
+
+fn markdown(list: &ExerciseCheckList) -> String {
+    let statistics = &list.statistics;
+    let mut md = String::from("# Rustlings results\n\n");
+    let _ = writeln!(
+        md,
+        "**{} / {}** exercises passed in {} s, scoring **{} / {}** points.\n",
+        statistics.total_succeeds,
+        statistics.total_exercises,
+        seconds(statistics.total_time_ms),
+        statistics.total_points,
+        statistics.max_points,
+    );
+    md.push_str("| Category | Passed | Required | Points |\n|---|---|---|---|\n");
+    for category in &list.categories {
+        let required = match category.required {
+            Some(required) if category.passed => format!("{required} ✓"),
+            Some(required) => format!("{required} ✗"),
+            None => "-".to_string(),
+        };
+        let _ = writeln!(
+            md,
+            "| {} | {} / {} | {required} | {} / {} |",
+            category.name, category.succeeds, category.total, category.points, category.max_points
+        );
+    }
+    let failures = list.exercises.iter().filter(|r| r.failure.is_some());
+    for (i, result) in failures.enumerate() {
+        if i == 0 {
+            md.push_str("\n## Failures\n");
+        }
+        let _ = writeln!(
+            md,
+            "\n### {} ({})\n\n`{}`",
+            result.name,
+            result.failure.unwrap(),
+            result.path
+        );
+        if !result.failed_tests.is_empty() {
+            let tests: Vec<String> = result
+                .failed_tests
+                .iter()
+                .map(|test| {
+                    if test.hidden {
+                        format!("`{}` (hidden)", test.name)
+                    } else {
+                        format!("`{}`", test.name)
+                    }
+                })
+                .collect();
+            let _ = writeln!(md, "\nFailing tests: {}", tests.join(", "));
+        }
+        let output = output(result);
+        if !output.trim().is_empty() {
+            let _ = writeln!(md, "\n```text\n{}\n```", output.trim_end());
+        }
+    }
+    md
+}
+
+// The captured output of an exercise, stderr first as that's where compilers complain
+pub fn output(result: &ExerciseResult) -> String {
+    [result.stderr.trim_end(), result.stdout.trim_end()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
@@ -1,6 +1,5 @@
-use std::process::Command;
-
-use crate::exercise::{Exercise, Mode};
+use crate::exercise::{Exercise, Mode, RunOptions};
+use crate::explain::annotate;
 use crate::verify::test;
 use indicatif::ProgressBar;
 
@@ -8,38 +7,30 @@ use indicatif::ProgressBar;
 // and run the ensuing binary.
 // The verbose argument helps determine whether or not to show
 // the output from the test harnesses (if the mode of the exercise is test)
-pub fn run(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
+pub fn run(exercise: &Exercise, options: &RunOptions, verbose: bool) -> Result<(), ()> {
+    // Running is asked for to see the exercise run, not what it printed last time
+    let options = &RunOptions {
+        cache: None,
+        ..options.clone()
+    };
     match exercise.mode {
-        Mode::Test => test(exercise, verbose)?,
-        Mode::Compile => compile_and_run(exercise)?,
-        Mode::Clippy => compile_and_run(exercise)?,
-        Mode::BuildScript => test(exercise, verbose)?,
+        Mode::Test => test(exercise, options, verbose)?,
+        Mode::Compile => compile_and_run(exercise, options)?,
+        Mode::Clippy => compile_and_run(exercise, options)?,
+        Mode::BuildScript => test(exercise, options, verbose)?,
     }
     Ok(())
 }
 
-// Resets the exercise by stashing the changes.
-pub fn reset(exercise: &Exercise) -> Result<(), ()> {
-    let command = Command::new("git")
-        .args(["stash", "--"])
-        .arg(&exercise.path)
-        .spawn();
-
-    match command {
-        Ok(_) => Ok(()),
-        Err(_) => Err(()),
-    }
-}
-
 // Invoke the rust compiler on the path of the given exercise
 // and run the ensuing binary.
 // This is strictly for non-test binaries, so output is displayed
-fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
+fn compile_and_run(exercise: &Exercise, options: &RunOptions) -> Result<(), ()> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
 
-    let compilation_result = exercise.compile();
+    let compilation_result = exercise.compile(options);
     let compilation = match compilation_result {
         Ok(compilation) => compilation,
         Err(output) => {
@@ -48,7 +39,7 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
                 "Compilation of {} failed!, Compiler error message:\n",
                 exercise
             );
-            println!("{}", output.stderr);
+            println!("{}", annotate(exercise, &output, true));
             return Err(());
         }
     };
@@ -67,7 +58,10 @@ fn compile_and_run(exercise: &Exercise) -> Result<(), ()> {
             println!("{}", output.stdout);
             println!("{}", output.stderr);
 
-            warn!("Ran {} with errors", exercise);
+            match output.exceeded {
+                Some(exceeded) => warn!("Ran {} with errors, it {}", exercise, exceeded),
+                None => warn!("Ran {} with errors", exercise),
+            }
             Err(())
         }
     }
@@ -0,0 +1,160 @@
+use crate::exercise::{Exercise, RunOptions};
+use crate::grade::grade;
+use crate::report::output;
+use console::style;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The directory the exercises live in, mirrored by the solutions directory
+const EXERCISES_DIR: &str = "exercises";
+const SOLUTIONS_DIR: &str = "solutions";
+
+// The path of the exercise's reference solution, which is the path of the
+// exercise with `exercises/` replaced by `solutions/`
+pub fn solution_path(exercise: &Exercise) -> PathBuf {
+    let relative = exercise
+        .path
+        .strip_prefix(EXERCISES_DIR)
+        .unwrap_or(&exercise.path);
+    Path::new(SOLUTIONS_DIR).join(relative)
+}
+
+// Print the reference solution of the exercise, or how it differs from
+// the exercise with `diff` set
+pub fn print_solution(exercise: &Exercise, diff: bool) -> Result<(), ()> {
+    let path = solution_path(exercise);
+    let solution = match fs::read_to_string(&path) {
+        Ok(solution) => solution,
+        Err(_) => {
+            println!("There is no solution for {} at {}", exercise.name, path.display());
+            return Err(());
+        }
+    };
+    if diff {
+        let source = fs::read_to_string(&exercise.path).map_err(|_| ())?;
+        print!("{}", self::diff(&source, &solution));
+    } else {
+        print!("{solution}");
+    }
+    Ok(())
+}
+
+// Grade the reference solutions in place of the exercises, proving that
+// every exercise can be solved. Returns whether all solutions passed.
+pub async fn check_solutions(exercises: &[Exercise], jobs: usize, options: &RunOptions) -> bool {
+    let (solved, missing): (Vec<&Exercise>, Vec<&Exercise>) = exercises
+        .iter()
+        .partition(|exercise| solution_path(exercise).exists());
+    for exercise in &missing {
+        warn!(
+            "{} has no solution at {}",
+            exercise.name,
+            solution_path(exercise).display()
+        );
+    }
+
+    let solutions = solved
+        .iter()
+        .map(|exercise| Exercise {
+            path: solution_path(exercise),
+            ..(*exercise).clone()
+        })
+        .collect();
+    let checked = grade(solutions, &[], jobs, options).await;
+    for result in checked.exercises.iter().filter(|r| !r.result) {
+        warn!(
+            "The solution of {} failed: {}",
+            result.name,
+            result.failure.unwrap()
+        );
+        println!("{}", output(result));
+    }
+
+    let passed = missing.is_empty() && checked.statistics.total_failures == 0;
+    if passed {
+        success!("All {} solutions passed", checked.statistics.total_exercises);
+    }
+    passed
+}
+
+// A line of a diff between two texts
+#[derive(PartialEq, Debug)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+// Show the changes turning `old` into `new` line by line, with removed
+// lines in red and prefixed by `-`, added ones in green and prefixed by `+`
+pub fn diff(old: &str, new: &str) -> String {
+    let mut rendered = String::new();
+    for line in diff_lines(old, new) {
+        let _ = match line {
+            DiffLine::Same(line) => writeln!(rendered, "  {line}"),
+            DiffLine::Removed(line) => writeln!(rendered, "{}", style(format!("- {line}")).red()),
+            DiffLine::Added(line) => writeln!(rendered, "{}", style(format!("+ {line}")).green()),
+        };
+    }
+    rendered
+}
+
+// Diff the lines using their longest common subsequence
+pub fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // common[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+    lines.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old = "fn main() {\n    let x = 5;\n    println!(\"{}\", x);\n}\n";
+        let new = "fn main() {\n    let x: i32 = 5;\n    println!(\"{}\", x);\n}\n";
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Same("fn main() {"),
+                DiffLine::Removed("    let x = 5;"),
+                DiffLine::Added("    let x: i32 = 5;"),
+                DiffLine::Same("    println!(\"{}\", x);"),
+                DiffLine::Same("}"),
+            ]
+        );
+        assert_eq!(diff_lines("a\n", "a\nb\n"), vec![DiffLine::Same("a"), DiffLine::Added("b")]);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::exercise::ExerciseList;
+use crate::validate::{self, Problem};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// The directory with the manifests of the tracks, which list exercises like
+// info.toml does. A track can include other tracks by their names.
+pub const TRACKS_DIR: &str = "tracks";
+
+// A file listing exercises, info.toml or the manifest of a track
+pub struct Manifest {
+    pub path: PathBuf,
+    pub text: String,
+    pub list: ExerciseList,
+}
+
+// Whether the track name can be one, naming a manifest right in tracks/
+// rather than pointing elsewhere
+pub fn is_name(track: &str) -> bool {
+    !track.is_empty() && !track.contains(['/', '\\']) && !track.contains("..")
+}
+
+// The manifest of the track, info.toml if none was chosen
+pub fn manifest_path(track: Option<&str>) -> PathBuf {
+    match track {
+        Some(track) => Path::new(TRACKS_DIR).join(format!("{track}.toml")),
+        None => PathBuf::from("info.toml"),
+    }
+}
+
+// The names of the tracks in the directory, sorted
+pub fn tracks(dir: &Path) -> Vec<String> {
+    let mut tracks: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "toml"))
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().to_string()))
+        .collect();
+    tracks.sort();
+    tracks
+}
+
+// Load the manifest of the track along with the ones it includes
+pub fn load(track: Option<&str>) -> Result<Vec<Manifest>, Problem> {
+    load_with(&manifest_path(track), &|path| fs::read_to_string(path).ok())
+}
+
+// Load the manifest and the ones it includes, reading them with `read`.
+// The included manifests come first, and every manifest is loaded once
+// even if several include it.
+pub fn load_with(path: &Path, read: &dyn Fn(&Path) -> Option<String>) -> Result<Vec<Manifest>, Problem> {
+    let mut manifests = Vec::new();
+    include(path, read, &mut Vec::new(), &mut manifests)?;
+    Ok(manifests)
+}
+
+fn include(
+    path: &Path,
+    read: &dyn Fn(&Path) -> Option<String>,
+    including: &mut Vec<PathBuf>,
+    manifests: &mut Vec<Manifest>,
+) -> Result<(), Problem> {
+    // The manifest that includes this one, to blame for problems with it
+    let blamed = including.last().map_or(path, PathBuf::as_path);
+    if including.iter().any(|included| included == path) {
+        let message = format!("including {} again leads to a cycle", path.display());
+        return Err(Problem::error(blamed, None, message));
+    }
+    if manifests.iter().any(|manifest| manifest.path == path) {
+        return Ok(());
+    }
+    let text = read(path).ok_or_else(|| Problem::error(blamed, None, format!("{} doesn't exist", path.display())))?;
+    let list = validate::parse(path, &text)?;
+
+    if let Some(track) = list.include.iter().find(|track| !is_name(track)) {
+        let message = format!("`{track}` isn't the name of a track, which can't contain `/`, `\\` or `..`");
+        return Err(Problem::error(path, None, message));
+    }
+    including.push(path.to_path_buf());
+    for track in &list.include {
+        include(&manifest_path(Some(track)), read, including, manifests)?;
+    }
+    including.pop();
+    manifests.push(Manifest {
+        path: path.to_path_buf(),
+        text,
+        list,
+    });
+    Ok(())
+}
+
+// Put the exercises of the manifests together in their order. The backend
+// is the one of the last manifest, which is the one of the track.
+pub fn combine(manifests: Vec<Manifest>) -> ExerciseList {
+    let mut combined = ExerciseList {
+        exercises: Vec::new(),
+        categories: Vec::new(),
+        backend: Default::default(),
+        include: Vec::new(),
+    };
+    for manifest in manifests {
+        combined.exercises.extend(manifest.list.exercises);
+        combined.categories.extend(manifest.list.categories);
+        combined.backend = manifest.list.backend;
+    }
+    combined
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn exercise(name: &str) -> String {
+        format!("[[exercises]]\nname = \"{name}\"\npath = \"{name}.rs\"\nmode = \"compile\"\nhint = \"\"\n")
+    }
+
+    fn load_from(files: &[(&str, String)], track: &str) -> Result<Vec<Manifest>, Problem> {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(track, text)| (manifest_path(Some(track)), text.clone()))
+            .collect();
+        load_with(&manifest_path(Some(track)), &|path| files.get(path).cloned())
+    }
+
+    #[test]
+    fn test_includes_come_first_and_once() {
+        let files = [
+            ("basics", exercise("variables1")),
+            ("iterators", format!("include = [\"basics\"]\n{}", exercise("iterators1"))),
+            ("algorithms", format!("include = [\"basics\", \"iterators\"]\n{}", exercise("algorithm1"))),
+        ];
+        let manifests = load_from(&files, "algorithms").unwrap();
+        let paths: Vec<&Path> = manifests.iter().map(|manifest| manifest.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            ["tracks/basics.toml", "tracks/iterators.toml", "tracks/algorithms.toml"].map(Path::new)
+        );
+        let names: Vec<String> = combine(manifests).exercises.into_iter().map(|e| e.name).collect();
+        assert_eq!(names, ["variables1", "iterators1", "algorithm1"]);
+    }
+
+    #[test]
+    fn test_include_problems() {
+        let files = [
+            ("a", format!("include = [\"b\"]\n{}", exercise("a1"))),
+            ("b", format!("include = [\"a\"]\n{}", exercise("b1"))),
+            ("c", "include = [\"missing\"]\n".to_string()),
+            ("d", "include = [\"../info\"]\n".to_string()),
+        ];
+        let cycle = load_from(&files, "a").err().unwrap();
+        assert_eq!(cycle.file, Path::new("tracks/b.toml"));
+        assert_eq!(cycle.message, "including tracks/a.toml again leads to a cycle");
+
+        let missing = load_from(&files, "c").err().unwrap();
+        assert_eq!(missing.file, Path::new("tracks/c.toml"));
+        assert_eq!(missing.message, "tracks/missing.toml doesn't exist");
+
+        let outside = load_from(&files, "d").err().unwrap();
+        assert_eq!(outside.file, Path::new("tracks/d.toml"));
+        assert_eq!(
+            outside.message,
+            "`../info` isn't the name of a track, which can't contain `/`, `\\` or `..`"
+        );
+        assert!(is_name("algorithms"));
+        assert!(!is_name("../info") && !is_name("a/b") && !is_name("a\\b") && !is_name(""));
+    }
+}
@@ -0,0 +1,547 @@
+use crate::exercise::{Exercise, ExerciseOutput, RunOptions};
+use crate::explain::{annotate, explanations};
+use crate::grade::{compile_failure, run_failure, FailureKind};
+use crate::line_editor::complete_line;
+use crate::list::{write_list, ListArgs};
+use crate::pristine::reset;
+use crate::progress::ProgressStore;
+use crate::solution::solution_path;
+use crate::watch::{complete, next_index, next_pending, parse_command, ShellCommand, WatchStatus, HELP};
+use crate::watcher::ExerciseWatcher;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::fs;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+// How long to wait for a key press before looking for changed files again
+const TICK: Duration = Duration::from_millis(100);
+
+const KEYS: &str = "↑↓ select  enter go to  n/p next/prev  s skip  f focus  r rerun  x reset  h hint  : command  ? help  PgUp/PgDn scroll  q quit";
+
+// What we know about an exercise in this session
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Status {
+    Done,
+    Pending,
+    Failing,
+}
+
+// The state of the full-screen watch mode
+struct App<'a> {
+    exercises: &'a [Exercise],
+    store: &'a mut ProgressStore,
+    // Cancelled by the watcher when a newer save makes the running check pointless
+    options: &'a RunOptions,
+    // Whether each exercise failed the last time it was checked
+    failing: Vec<bool>,
+    // The exercise whose output is shown
+    current: usize,
+    // The exercise selected in the list
+    list: ListState,
+    // The output of the last check of the current exercise, or what a
+    // command showed instead, like a solution, along with its title
+    output: String,
+    title: String,
+    scroll: u16,
+    show_hint: bool,
+    success_hints: bool,
+    // Whether only the current exercise is checked on save
+    focus: bool,
+    // Whether the next `y` confirms resetting the current exercise
+    confirm_reset: bool,
+    // Whether the user asked to quit, possibly while an exercise was checked
+    quit: bool,
+    message: String,
+    // The command being typed after `:`, which are those of the watch shell
+    prompt: Option<String>,
+    // The commands typed before, the latest last
+    history: Vec<String>,
+    // The entry of the history shown in the prompt, the end for a new command
+    browsing: usize,
+    // The names of the exercises, to complete commands with
+    names: Vec<String>,
+    // The output of the last exercise that failed, for `explain`
+    last_failure: String,
+}
+
+// Run watch mode as a full-screen terminal UI.
+// It has a list of the exercises along with their status, the output of
+// the current exercise and its hint, which is toggled with a key.
+pub fn watch(
+    exercises: &[Exercise],
+    store: &mut ProgressStore,
+    options: &RunOptions,
+    watcher: &ExerciseWatcher,
+    success_hints: bool,
+) -> notify::Result<WatchStatus> {
+    let mut terminal = ratatui::try_init()?;
+    let app = App {
+        exercises,
+        store,
+        options,
+        failing: vec![false; exercises.len()],
+        current: 0,
+        list: ListState::default(),
+        output: String::new(),
+        title: String::new(),
+        scroll: 0,
+        show_hint: false,
+        success_hints,
+        focus: false,
+        confirm_reset: false,
+        quit: false,
+        message: String::new(),
+        prompt: None,
+        history: Vec::new(),
+        browsing: 0,
+        names: exercises.iter().map(|e| e.name.clone()).collect(),
+        last_failure: String::new(),
+    };
+    let status = run(&mut terminal, app, watcher);
+    ratatui::try_restore()?;
+    Ok(status?)
+}
+
+fn run(
+    terminal: &mut DefaultTerminal,
+    mut app: App,
+    watcher: &ExerciseWatcher,
+) -> std::io::Result<WatchStatus> {
+    let exercises = app.exercises;
+    match next_pending(exercises, app.store, 0) {
+        Some(next) => app.check(terminal, next, true)?,
+        None => return Ok(WatchStatus::Finished),
+    }
+
+    loop {
+        if app.quit {
+            return Ok(WatchStatus::Unfinished);
+        }
+        watcher.set_focus(app.focus.then_some(app.current));
+        terminal.draw(|frame| app.draw(frame))?;
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if app.prompt.is_some() {
+                    app.edit_prompt(terminal, key)?;
+                } else {
+                    app.press(terminal, key)?;
+                }
+            }
+        }
+
+        let changed: Vec<Vec<usize>> = watcher.try_iter().collect();
+        for affected in changed {
+            if app.quit {
+                break;
+            }
+            if app.focus {
+                if affected.contains(&app.current) {
+                    app.check(terminal, app.current, false)?;
+                }
+            } else if let Some(&first) = affected.first() {
+                app.check(terminal, first, true)?;
+            }
+        }
+        if next_pending(exercises, app.store, 0).is_none() {
+            return Ok(WatchStatus::Finished);
+        }
+    }
+}
+
+impl App<'_> {
+    fn press(&mut self, terminal: &mut DefaultTerminal, key: KeyEvent) -> std::io::Result<()> {
+        let confirm_reset = std::mem::take(&mut self.confirm_reset);
+        match key.code {
+            _ if is_quit(key) => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.select(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.select(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::Enter => {
+                let selected = self.list.selected().unwrap_or(self.current);
+                self.check(terminal, selected, false)?;
+            }
+            KeyCode::Char('n') => self.execute(terminal, ShellCommand::Next)?,
+            KeyCode::Char('p') => self.execute(terminal, ShellCommand::Prev)?,
+            KeyCode::Char('s') => self.execute(terminal, ShellCommand::Skip)?,
+            KeyCode::Char('f') => self.execute(terminal, ShellCommand::Focus)?,
+            KeyCode::Char('r') => self.execute(terminal, ShellCommand::Run(None))?,
+            KeyCode::Char('h') => self.show_hint = !self.show_hint,
+            KeyCode::Char('x') => {
+                self.confirm_reset = true;
+                self.message = format!(
+                    "Reset {} to its original state? Press y to confirm",
+                    self.exercises[self.current].name
+                );
+            }
+            KeyCode::Char('y') if confirm_reset => self.execute(terminal, ShellCommand::Reset(None))?,
+            KeyCode::Char(':') => {
+                self.prompt = Some(String::new());
+                self.browsing = self.history.len();
+                self.message.clear();
+            }
+            KeyCode::Char('?') => self.show("Help", help()),
+            _ => self.message.clear(),
+        }
+        Ok(())
+    }
+
+    // Edit the command being typed, with tab-completion and the earlier
+    // commands on the arrow keys like in the watch shell
+    fn edit_prompt(&mut self, terminal: &mut DefaultTerminal, key: KeyEvent) -> std::io::Result<()> {
+        let mut line = self.prompt.take().unwrap_or_default();
+        match key.code {
+            KeyCode::Enter => {
+                if !line.trim().is_empty() && self.history.last() != Some(&line) {
+                    self.history.push(line.clone());
+                }
+                return self.command(terminal, line.trim());
+            }
+            KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            // Deleting past the start leaves the prompt
+            KeyCode::Backspace if line.pop().is_none() => return Ok(()),
+            KeyCode::Tab => {
+                let completions = complete(&line, &self.names);
+                self.message = complete_line(&mut line, &completions).join("  ");
+            }
+            KeyCode::Up if self.browsing > 0 => {
+                self.browsing -= 1;
+                line = self.history[self.browsing].clone();
+            }
+            KeyCode::Down if self.browsing < self.history.len() => {
+                self.browsing += 1;
+                line = self.history.get(self.browsing).cloned().unwrap_or_default();
+            }
+            KeyCode::Char(c) => line.push(c),
+            _ => {}
+        }
+        self.prompt = Some(line);
+        Ok(())
+    }
+
+    // Carry out a command typed at the prompt
+    fn command(&mut self, terminal: &mut DefaultTerminal, input: &str) -> std::io::Result<()> {
+        if let Some(command) = parse_command(input) {
+            return self.execute(terminal, command);
+        }
+        match input {
+            "" => {}
+            "hint" => self.show_hint = true,
+            "clear" => {
+                self.output.clear();
+                self.message.clear();
+            }
+            "help" => self.show("Help", help()),
+            _ => match input.strip_prefix('!') {
+                Some(cmd) => self.shell(cmd),
+                None => self.message = format!("unknown command: {input}"),
+            },
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, terminal: &mut DefaultTerminal, command: ShellCommand) -> std::io::Result<()> {
+        let exercises = self.exercises;
+        match command {
+            ShellCommand::Goto(name) => {
+                if let Some(index) = self.find(Some(name)) {
+                    self.check(terminal, index, false)?;
+                }
+            }
+            ShellCommand::Next => self.check(terminal, next_index(exercises, self.current), false)?,
+            ShellCommand::Prev => self.check(terminal, self.current.saturating_sub(1), false)?,
+            ShellCommand::Skip => {
+                let next = next_pending(exercises, self.store, self.current + 1);
+                self.check(terminal, next.unwrap_or(self.current), false)?;
+            }
+            ShellCommand::Focus => {
+                self.focus = !self.focus;
+                self.message = if self.focus {
+                    format!("Focusing on {}, other exercises aren't checked on save", exercises[self.current].name)
+                } else {
+                    format!("No longer focusing on {}", exercises[self.current].name)
+                };
+            }
+            ShellCommand::Run(name) => {
+                if let Some(index) = self.find(name) {
+                    self.check(terminal, index, false)?;
+                }
+            }
+            ShellCommand::Reset(name) => {
+                if let Some(index) = self.find(name) {
+                    let exercise = &exercises[index];
+                    match reset(exercise) {
+                        Ok(Some(backup)) => {
+                            self.check(terminal, index, false)?;
+                            self.message = format!("Reset {exercise}, your version is backed up in {}", backup.display());
+                        }
+                        Ok(None) => self.message = format!("{exercise} is already in its original state"),
+                        Err(e) => self.message = e,
+                    }
+                }
+            }
+            ShellCommand::List => {
+                let mut list = Vec::new();
+                write_list(&mut list, exercises, self.store, &ListArgs::default())?;
+                self.show("Exercises", String::from_utf8_lossy(&list).into_owned());
+            }
+            ShellCommand::Solution(name) => {
+                if let Some(exercise) = self.find(name).map(|index| &exercises[index]) {
+                    let path = solution_path(exercise);
+                    if !self.store.is_done(exercise) {
+                        self.message = format!("Solve {} first to see its solution!", exercise.name);
+                    } else if let Ok(solution) = fs::read_to_string(&path) {
+                        self.show(&format!("Solution of {}", exercise.name), solution);
+                    } else {
+                        self.message = format!("There is no solution for {} at {}", exercise.name, path.display());
+                    }
+                }
+            }
+            ShellCommand::Explain => match explanations(&self.last_failure) {
+                Some(explanations) => self.show("Explanations", explanations),
+                None => self.message = "The last failure didn't have any error codes to explain".to_string(),
+            },
+            ShellCommand::Quit => self.quit = true,
+        }
+        Ok(())
+    }
+
+    // Execute a command and show its output, like `!rustc --explain E0381`
+    fn shell(&mut self, cmd: &str) {
+        let parts: Vec<&str> = cmd.split_whitespace().collect();
+        match parts.split_first() {
+            None => self.message = "no command provided".to_string(),
+            Some((program, args)) => match Command::new(program).args(args).output() {
+                Ok(output) => {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    self.show(cmd, format!("{stdout}{stderr}"));
+                }
+                Err(e) => self.message = format!("failed to execute command `{cmd}`: {e}"),
+            },
+        }
+    }
+
+    // Show the text in place of the output of the current exercise
+    fn show(&mut self, title: &str, text: String) {
+        self.title = title.to_string();
+        self.output = console::strip_ansi_codes(&text).into_owned();
+        self.scroll = 0;
+        self.message.clear();
+    }
+
+    // The exercise with the given name, or the current one without a name
+    fn find(&mut self, name: Option<String>) -> Option<usize> {
+        let name = match name {
+            Some(name) => name,
+            None => return Some(self.current),
+        };
+        let index = self.exercises.iter().position(|e| e.name == name);
+        if index.is_none() {
+            self.message = format!("No exercise found for '{name}'!");
+        }
+        index
+    }
+
+    fn status(&self, index: usize) -> Status {
+        if self.store.is_done(&self.exercises[index]) {
+            Status::Done
+        } else if self.failing[index] {
+            Status::Failing
+        } else {
+            Status::Pending
+        }
+    }
+
+    fn select(&mut self, delta: isize) {
+        let selected = self.list.selected().unwrap_or(self.current) as isize + delta;
+        let last = self.exercises.len().saturating_sub(1) as isize;
+        self.list.select(Some(selected.clamp(0, last) as usize));
+    }
+
+    // Compile and run the exercise and show how it went. With `advance` set,
+    // a done exercise is followed by the next pending one after it.
+    fn check(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        index: usize,
+        advance: bool,
+    ) -> std::io::Result<()> {
+        let exercise = &self.exercises[index];
+        self.current = index;
+        self.list.select(Some(index));
+        self.message = format!("Checking {exercise}...");
+        terminal.draw(|frame| self.draw(frame))?;
+
+        let (failure, output) = self.check_in_background(terminal, exercise)?;
+        if self.quit {
+            return Ok(());
+        }
+        if self.options.cancel.reset() {
+            self.message = format!("Checking {exercise} again as it was saved...");
+            return Ok(());
+        }
+        self.failing[index] = failure.is_some();
+        if failure.is_some() {
+            self.last_failure = format!("{}\n{}", output.stdout, output.messages(false));
+        }
+        self.title = exercise.to_string();
+        self.scroll = 0;
+        let messages = annotate(exercise, &output, false);
+        self.output = [output.stdout.trim_end(), messages.trim_end()]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| console::strip_ansi_codes(s).into_owned())
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.message = match failure {
+            Some(failure) => format!("{} failed: {failure}", exercise.name),
+            None if exercise.looks_done() => {
+                self.store.record_done(exercise);
+                format!("{} is done!", exercise.name)
+            }
+            None => format!(
+                "{} passes! Remove the `I AM NOT DONE` comment to move on",
+                exercise.name
+            ),
+        };
+        if failure.is_none() && self.success_hints {
+            self.show_hint = true;
+        }
+
+        if advance && self.status(index) == Status::Done {
+            if let Some(next) = next_pending(self.exercises, self.store, index) {
+                let done = std::mem::take(&mut self.message);
+                self.check(terminal, next, true)?;
+                self.message = format!("{done} {}", self.message);
+            }
+        }
+        Ok(())
+    }
+
+    // Check the exercise on a worker thread while listening to the keyboard,
+    // so that quitting stops the check right away instead of waiting for it
+    fn check_in_background(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        exercise: &Exercise,
+    ) -> std::io::Result<(Option<FailureKind>, ExerciseOutput)> {
+        self.options.cancel.reset();
+        thread::scope(|scope| {
+            let worker = scope.spawn(|| check(exercise, self.options));
+            while !worker.is_finished() {
+                if event::poll(TICK)? {
+                    if let Event::Key(key) = event::read()? {
+                        if key.kind == KeyEventKind::Press && is_quit(key) {
+                            self.quit = true;
+                            self.options.cancel.cancel();
+                            self.message = "Quitting...".to_string();
+                        }
+                    }
+                }
+                terminal.draw(|frame| self.draw(frame))?;
+            }
+            Ok(worker.join().unwrap())
+        })
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, footer] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).areas(frame.area());
+        let [list_area, right] =
+            Layout::horizontal([Constraint::Length(28), Constraint::Min(1)]).areas(main);
+
+        let items: Vec<ListItem> = (0..self.exercises.len())
+            .map(|i| {
+                let (symbol, color) = match self.status(i) {
+                    Status::Done => ("✓", Color::Green),
+                    Status::Pending => ("•", Color::Yellow),
+                    Status::Failing => ("✗", Color::Red),
+                };
+                let mut name = Style::default();
+                if i == self.current {
+                    name = name.add_modifier(Modifier::BOLD);
+                }
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{symbol} "), Style::default().fg(color)),
+                    Span::styled(self.exercises[i].name.as_str(), name),
+                ]))
+            })
+            .collect();
+        let done = (0..self.exercises.len())
+            .filter(|&i| self.status(i) == Status::Done)
+            .count();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                " Exercises {done}/{} ",
+                self.exercises.len()
+            )))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let exercise = &self.exercises[self.current];
+        let output_area = if self.show_hint {
+            let [output_area, hint_area] =
+                Layout::vertical([Constraint::Percentage(70), Constraint::Percentage(30)])
+                    .areas(right);
+            let hint = Paragraph::new(exercise.hint.as_str())
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title(" Hint "));
+            frame.render_widget(hint, hint_area);
+            output_area
+        } else {
+            right
+        };
+        let output = Paragraph::new(self.output.as_str())
+            .scroll((self.scroll, 0))
+            .block(Block::default().borders(Borders::ALL).title(format!(" {} ", self.title)));
+        frame.render_widget(output, output_area);
+
+        let mode = if self.focus { "[focus] " } else { "" };
+        let keys = match &self.prompt {
+            Some(line) => {
+                let cursor = footer.x + 1 + line.chars().count() as u16;
+                frame.set_cursor_position((cursor, footer.y + 1));
+                Line::from(format!(":{line}"))
+            }
+            None => Line::styled(KEYS, Style::default().fg(Color::DarkGray)),
+        };
+        let footer_text = vec![Line::from(format!("{mode}{}", self.message)), keys];
+        frame.render_widget(Paragraph::new(footer_text), footer);
+    }
+}
+
+// The commands of the watch shell, typed after `:` here
+fn help() -> String {
+    format!("{HELP}\nPress : to type a command, tab to complete commands and exercise names and the arrow keys for earlier commands.\n\n{KEYS}\n")
+}
+
+fn is_quit(key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('q') => true,
+        KeyCode::Char('c') => key.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+// Compile and run the exercise without printing anything
+fn check(exercise: &Exercise, options: &RunOptions) -> (Option<FailureKind>, ExerciseOutput) {
+    let compiled = match exercise.compile(options) {
+        Ok(compiled) => compiled,
+        Err(output) => return (Some(compile_failure(exercise, &output)), output),
+    };
+    match compiled.run() {
+        Ok(output) => (None, output),
+        Err(output) => (Some(run_failure(exercise, &output)), output),
+    }
+}
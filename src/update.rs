@@ -0,0 +1,315 @@
+use crate::embedded;
+use crate::exercise::Exercise;
+use crate::merge::{merge, Merged};
+use crate::pristine;
+use crate::progress::ProgressStore;
+use crate::track::{self, TRACKS_DIR};
+use std::collections::BTreeMap;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+
+// A set of exercises to update to, along with the other files under
+// exercises/ and the tracks
+pub struct Curriculum {
+    pub info_toml: String,
+    // The contents of every file by its path relative to info.toml,
+    // using forward slashes
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+impl Curriculum {
+    // The exercises the binary was built with
+    pub fn embedded() -> Self {
+        Curriculum {
+            info_toml: embedded::INFO_TOML.to_string(),
+            files: embedded::FILES
+                .iter()
+                .map(|(path, contents)| (path.to_string(), contents.to_vec()))
+                .collect(),
+        }
+    }
+
+    // The exercises in another rustlings directory
+    pub fn from_dir(dir: &Path) -> Result<Self, String> {
+        let info = dir.join("info.toml");
+        let info_toml = fs::read_to_string(&info).map_err(|e| format!("Failed to read {}: {e}", info.display()))?;
+        let mut files = BTreeMap::new();
+        collect(dir, &dir.join("exercises"), &mut files)
+            .map_err(|e| format!("Failed to read the exercises in {}: {e}", dir.display()))?;
+        let tracks = dir.join(TRACKS_DIR);
+        if tracks.is_dir() {
+            collect(dir, &tracks, &mut files)
+                .map_err(|e| format!("Failed to read the tracks in {}: {e}", dir.display()))?;
+        }
+        Ok(Curriculum { info_toml, files })
+    }
+
+    fn file(&self, path: &Path) -> Option<&[u8]> {
+        self.files.get(&slashed(path)).map(Vec::as_slice)
+    }
+
+    fn read(&self, path: &Path) -> Option<String> {
+        if path == Path::new("info.toml") {
+            return Some(self.info_toml.clone());
+        }
+        String::from_utf8(self.file(path)?.to_vec()).ok()
+    }
+
+    fn tracks(&self) -> Vec<String> {
+        self.files
+            .keys()
+            .filter_map(|path| path.strip_prefix("tracks/")?.strip_suffix(".toml"))
+            .map(String::from)
+            .collect()
+    }
+}
+
+// Every exercise of info.toml and the tracks, reading the manifests with `read`
+fn all_exercises(tracks: &[String], read: &dyn Fn(&Path) -> Option<String>) -> Result<Vec<Exercise>, String> {
+    let mut exercises: Vec<Exercise> = Vec::new();
+    for track in iter::once(None).chain(tracks.iter().map(|track| Some(track.as_str()))) {
+        let manifests = track::load_with(&track::manifest_path(track), read).map_err(|problem| problem.to_string())?;
+        for exercise in track::combine(manifests).exercises {
+            if !exercises.iter().any(|known| known.name == exercise.name) {
+                exercises.push(exercise);
+            }
+        }
+    }
+    Ok(exercises)
+}
+
+// Every file in the directory by its path relative to the root
+fn collect(root: &Path, dir: &Path, files: &mut BTreeMap<String, Vec<u8>>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            files.insert(slashed(relative), fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+fn slashed(path: &Path) -> String {
+    let components: Vec<String> = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    components.join("/")
+}
+
+// What updating a single exercise comes down to
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    // The exercise is new, or its file was missing
+    Add,
+    // Your version is the updated one already
+    Unchanged,
+    // The update didn't change the exercise, so your version stays
+    KeepYours,
+    // You hadn't touched the exercise, so it's replaced with the update
+    Replace,
+    // Both you and the update changed the exercise
+    Merge { text: String, conflicts: usize },
+    // You changed the exercise but its original version wasn't kept,
+    // so there's nothing to merge the update with
+    NoOriginal,
+}
+
+// Decide how to update your version of an exercise, given the original
+// version you started from and the updated one
+pub fn change(original: Option<&str>, yours: Option<&str>, updated: &str) -> Change {
+    let yours = match yours {
+        Some(yours) => yours,
+        None => return Change::Add,
+    };
+    if yours == updated {
+        return Change::Unchanged;
+    }
+    match original {
+        Some(original) if original == updated => Change::KeepYours,
+        Some(original) if original == yours => Change::Replace,
+        Some(original) => {
+            let Merged { text, conflicts } = merge(original, yours, updated);
+            Change::Merge { text, conflicts }
+        }
+        None => Change::NoOriginal,
+    }
+}
+
+// Update the exercises of every track in the current directory to the
+// curriculum. Exercises are matched by their name or by one of the aliases
+// the updated ones list, so that renamed exercises keep their changes and
+// the progress on every track.
+// Returns whether every exercise could be updated without conflicts.
+pub fn update(curriculum: &Curriculum) -> Result<bool, String> {
+    let tracks = track::tracks(Path::new(TRACKS_DIR));
+    let exercises = all_exercises(&tracks, &|path| fs::read_to_string(path).ok())?;
+    let updated = all_exercises(&curriculum.tracks(), &|path| curriculum.read(path))
+        .map_err(|e| format!("The update is broken: {e}"))?;
+    let mut stores: Vec<ProgressStore> = iter::once(None)
+        .chain(tracks.iter().map(|track| Some(track.as_str())))
+        .map(ProgressStore::load)
+        .collect();
+    let mut clean = true;
+    let mut matched = Vec::new();
+    for exercise in &updated {
+        let contents = curriculum
+            .file(&exercise.path)
+            .ok_or_else(|| format!("The update has no file for {exercise}"))?;
+        let contents = String::from_utf8_lossy(contents);
+        let old = exercises
+            .iter()
+            .find(|old| old.name == exercise.name)
+            .or_else(|| exercises.iter().find(|old| exercise.aliases.contains(&old.name)));
+        if let Some(old) = old {
+            matched.push(&old.name);
+        }
+        clean &= update_exercise(exercise, old, &contents)?;
+        if let Some(old) = old.filter(|old| old.name != exercise.name) {
+            for store in &mut stores {
+                store
+                    .rename(&old.name, &exercise.name)
+                    .map_err(|e| format!("Failed to carry over the progress of {old}: {e}"))?;
+            }
+            println!("Renamed {old} to {exercise}");
+        }
+        pristine::keep_original(exercise, &contents)
+            .map_err(|e| format!("Failed to keep the updated version of {exercise}: {e}"))?;
+    }
+
+    // Other files under exercises/, like the READMEs, and the tracks
+    for (path, contents) in &curriculum.files {
+        let path = PathBuf::from(path);
+        if updated.iter().any(|exercise| exercise.path == path) {
+            continue;
+        }
+        replace_file(&path, contents)?;
+    }
+    replace_file(Path::new("info.toml"), curriculum.info_toml.as_bytes())?;
+
+    for exercise in exercises.iter().filter(|exercise| !matched.contains(&&exercise.name)) {
+        println!(
+            "{exercise} is no longer part of rustlings, {} was left as it is",
+            exercise.path.display()
+        );
+    }
+    Ok(clean)
+}
+
+// Update a single exercise, which was `old` before the update.
+// Returns false if there were conflicts to resolve.
+fn update_exercise(exercise: &Exercise, old: Option<&Exercise>, updated: &str) -> Result<bool, String> {
+    let path = old.map_or(&exercise.path, |old| &old.path);
+    let yours = fs::read_to_string(path).ok();
+    // A clone of the repository never kept its originals, so the shipped
+    // version it most likely started from is merged with instead
+    let original = old
+        .and_then(pristine::kept_original)
+        .or_else(|| pristine::shipped_original(old?, yours.as_deref()?));
+    let change = change(original.as_deref(), yours.as_deref(), updated);
+    let write = |contents: &str| {
+        if let Some(dir) = exercise.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        fs::write(&exercise.path, contents).map_err(|e| format!("Failed to update {exercise}: {e}"))
+    };
+    let backup = || {
+        let yours = yours.as_deref().unwrap_or_default();
+        pristine::backup(&exercise.name, path, yours).map_err(|e| format!("Failed to back up {exercise}: {e}"))
+    };
+    let mut clean = true;
+    match change {
+        Change::Add => {
+            write(updated)?;
+            println!("Added {exercise}");
+        }
+        Change::Unchanged => write(updated)?,
+        Change::KeepYours => write(yours.as_deref().unwrap_or_default())?,
+        Change::Replace => {
+            let backup = backup()?;
+            write(updated)?;
+            println!("Updated {exercise}, the previous version is backed up in {}", backup.display());
+        }
+        Change::Merge { text, conflicts } => {
+            let backup = backup()?;
+            write(&text)?;
+            if conflicts == 0 {
+                success!(
+                    "Merged the update of {} with your changes, your version is backed up in {}",
+                    exercise,
+                    backup.display()
+                );
+            } else {
+                let conflicts = match conflicts {
+                    1 => "a conflict".to_string(),
+                    n => format!("{n} conflicts"),
+                };
+                warn!(
+                    "The update of {} conflicts with your changes, resolve {} marked in it (your version is backed up in {})",
+                    exercise,
+                    conflicts,
+                    backup.display()
+                );
+                clean = false;
+            }
+        }
+        Change::NoOriginal => {
+            write(yours.as_deref().unwrap_or_default())?;
+            warn!(
+                "Kept your version of {}, there's no original version to merge the update with. Run `rustlings reset {}` to get the updated one.",
+                exercise,
+                exercise.name
+            );
+        }
+    }
+    // The exercise was moved
+    if path != &exercise.path && yours.is_some() {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+    }
+    Ok(clean)
+}
+
+// Replace a file that isn't an exercise, backing up the current version if it differs
+fn replace_file(path: &Path, contents: &[u8]) -> Result<(), String> {
+    match fs::read(path) {
+        Ok(current) if current == contents => return Ok(()),
+        Ok(current) => {
+            pristine::backup(&slashed(path), path, &String::from_utf8_lossy(&current))
+                .map_err(|e| format!("Failed to back up {}: {e}", path.display()))?;
+        }
+        Err(_) => {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+            }
+        }
+    }
+    fs::write(path, contents).map_err(|e| format!("Failed to update {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_change() {
+        let original = "fn main() {\n    // I AM NOT DONE\n    let x = 5;\n    println!(\"{x}\");\n}\n";
+        let yours = "fn main() {\n    let x = 5;\n    println!(\"{x}\");\n}\n";
+        let updated = "fn main() {\n    // I AM NOT DONE\n    let x = 5;\n    println!(\"x is {x}\");\n}\n";
+
+        assert_eq!(change(Some(original), None, updated), Change::Add);
+        assert_eq!(change(Some(original), Some(updated), updated), Change::Unchanged);
+        assert_eq!(change(Some(original), Some(yours), original), Change::KeepYours);
+        assert_eq!(change(Some(original), Some(original), updated), Change::Replace);
+        assert_eq!(change(None, Some(yours), updated), Change::NoOriginal);
+        assert_eq!(
+            change(Some(original), Some(yours), updated),
+            Change::Merge {
+                text: "fn main() {\n    let x = 5;\n    println!(\"x is {x}\");\n}\n".to_string(),
+                conflicts: 0
+            }
+        );
+    }
+}
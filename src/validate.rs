@@ -0,0 +1,438 @@
+use crate::exercise::{Exercise, ExerciseList};
+use crate::track::Manifest;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::value::{Table, Value};
+
+// A problem found in info.toml or the manifest of a track, or with the
+// exercises they list
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    pub severity: Severity,
+    // The file the problem is in
+    pub file: PathBuf,
+    // The line and column in the file the problem is at, counting from 1
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    // Rustlings can't work with the exercises like this
+    Error,
+    // Likely a mistake, but the exercises still work
+    Warning,
+}
+
+impl Problem {
+    pub fn error(file: &Path, location: Option<(usize, usize)>, message: String) -> Self {
+        Problem {
+            severity: Severity::Error,
+            file: file.to_path_buf(),
+            location,
+            message,
+        }
+    }
+
+    pub fn warning(file: &Path, location: Option<(usize, usize)>, message: String) -> Self {
+        Problem {
+            severity: Severity::Warning,
+            file: file.to_path_buf(),
+            location,
+            message,
+        }
+    }
+}
+
+// The keys rustlings reads from a manifest, its exercises and its categories
+const MANIFEST_KEYS: &[&str] = &["exercises", "categories", "backend", "include"];
+const EXERCISE_KEYS: &[&str] = &[
+    "name",
+    "path",
+    "mode",
+    "hint",
+    "timeout",
+    "points",
+    "category",
+    "tests_fingerprint",
+    "hidden_tests",
+    "watch",
+    "aliases",
+    "dependencies",
+];
+const CATEGORY_KEYS: &[&str] = &["name", "required"];
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some((line, column)) = self.location {
+            write!(f, ":{line}:{column}")?;
+        }
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, ": {severity}: {}", self.message)
+    }
+}
+
+// Parse info.toml or the manifest of a track, pointing at where it doesn't
+// match what rustlings expects, like an unknown mode or a missing key
+pub fn parse(file: &Path, info_toml: &str) -> Result<ExerciseList, Problem> {
+    toml::from_str(info_toml).map_err(|e| {
+        let mut message = e.to_string();
+        // The location is part of the message, but it's shown in front already
+        if let Some(at) = message.rfind(" at line ") {
+            message.truncate(at);
+        }
+        // The toml crate points at the end of the file for unknown keys and
+        // values, so look for them instead
+        let location = pinpoint(info_toml, &message)
+            .or_else(|| e.line_col().map(|(line, column)| (line + 1, column + 1)));
+        Problem::error(file, location, message)
+    })
+}
+
+// Check that every exercise of a track has a unique name and an existing
+// file, and that the rubric fits the categories. The manifests are the one
+// of the track and the ones it includes.
+pub fn check(manifests: &[Manifest], root: &Path) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    let mut paths: HashMap<&Path, usize> = HashMap::new();
+    for manifest in manifests {
+        let mut error = |location, message| problems.push(Problem::error(&manifest.path, location, message));
+        // How often the name and path were set in this manifest, to point at
+        // the right one
+        let mut names_here: HashMap<&str, usize> = HashMap::new();
+        let mut paths_here: HashMap<&Path, usize> = HashMap::new();
+        for exercise in &manifest.list.exercises {
+            let nth = names_here.entry(&exercise.name).or_default();
+            let seen = names.entry(&exercise.name).or_default();
+            if *seen > 0 {
+                error(
+                    locate(&manifest.text, "name", &exercise.name, *nth),
+                    format!("there's more than one exercise named `{}`", exercise.name),
+                );
+            }
+            *seen += 1;
+            *nth += 1;
+
+            let path = exercise.path.to_string_lossy();
+            let nth = paths_here.entry(&exercise.path).or_default();
+            let seen = paths.entry(&exercise.path).or_default();
+            if *seen > 0 {
+                error(
+                    locate(&manifest.text, "path", &path, *nth),
+                    format!("there's more than one exercise at `{path}`"),
+                );
+            } else if !root.join(&exercise.path).is_file() {
+                error(
+                    locate(&manifest.text, "path", &path, *nth),
+                    format!("the file `{path}` of the exercise `{}` doesn't exist", exercise.name),
+                );
+            }
+            *seen += 1;
+            *nth += 1;
+        }
+    }
+    // The rubric can only ask for exercises the categories have
+    let mut sizes: HashMap<String, usize> = HashMap::new();
+    for exercise in manifests.iter().flat_map(|manifest| &manifest.list.exercises) {
+        *sizes.entry(exercise.category()).or_default() += 1;
+    }
+    for manifest in manifests {
+        for rubric in &manifest.list.categories {
+            let location = locate(&manifest.text, "name", &rubric.name, 0);
+            match sizes.get(&rubric.name).copied().unwrap_or_default() {
+                0 => problems.push(Problem::error(
+                    &manifest.path,
+                    location,
+                    format!("the category `{}` has no exercises", rubric.name),
+                )),
+                size if rubric.required > size => problems.push(Problem::error(
+                    &manifest.path,
+                    location,
+                    format!(
+                        "the category `{}` requires {} exercises, but has only {size}",
+                        rubric.name, rubric.required
+                    ),
+                )),
+                _ => {}
+            }
+        }
+    }
+    for manifest in manifests {
+        for exercise in &manifest.list.exercises {
+            for alias in exercise.aliases.iter().filter(|alias| names.contains_key(alias.as_str())) {
+                problems.push(Problem::error(
+                    &manifest.path,
+                    locate(&manifest.text, "name", &exercise.name, 0),
+                    format!("the alias `{alias}` of `{}` is the name of another exercise", exercise.name),
+                ));
+            }
+        }
+    }
+    problems
+}
+
+// Warn about the keys of the manifest rustlings doesn't know, like a
+// misspelled optional one. They're ignored, so that manifests written for a
+// newer rustlings still work.
+pub fn unknown_keys(manifest: &Manifest) -> Vec<Problem> {
+    let table: Table = match toml::from_str(&manifest.text) {
+        Ok(table) => table,
+        Err(_) => return Vec::new(),
+    };
+    let tables = |key: &str| -> Vec<&Table> {
+        let tables = table.get(key).and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+        tables.iter().filter_map(Value::as_table).collect()
+    };
+    let name = |table: &Table| table.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+    let mut unknown = unknown_in(&table, MANIFEST_KEYS, "the manifest");
+    for exercise in tables("exercises") {
+        unknown.extend(unknown_in(exercise, EXERCISE_KEYS, &format!("the exercise `{}`", name(exercise))));
+    }
+    for category in tables("categories") {
+        unknown.extend(unknown_in(category, CATEGORY_KEYS, &format!("the category `{}`", name(category))));
+    }
+
+    // How often each key was found so far, to point at the right one
+    let mut seen: HashMap<&String, usize> = HashMap::new();
+    let mut problems: Vec<Problem> = unknown
+        .into_iter()
+        .map(|(key, of)| {
+            let nth = seen.entry(key).or_default();
+            let location = locate_key(&manifest.text, key, |_| true, *nth).map(|(line, column, _)| (line, column));
+            *nth += 1;
+            let message = format!("rustlings doesn't know the key `{key}` of {of}, so it's ignored");
+            Problem::warning(&manifest.path, location, message)
+        })
+        .collect();
+    problems.sort_by_key(|problem| problem.location);
+    problems
+}
+
+// The keys of the table that aren't known, along with what they were set for
+fn unknown_in<'a>(table: &'a Table, known: &[&str], of: &str) -> Vec<(&'a String, String)> {
+    table
+        .keys()
+        .filter(|key| !known.contains(&key.as_str()))
+        .map(|key| (key, of.to_string()))
+        .collect()
+}
+
+// Warn about the Rust files under `exercises/` of the root that none of
+// the exercises, of any track, uses
+pub fn unlisted<'a>(exercises: impl Iterator<Item = &'a Exercise> + Clone, root: &Path) -> Vec<Problem> {
+    let mut files = Vec::new();
+    rust_files(&root.join("exercises"), &mut files);
+    files.sort();
+    files
+        .iter()
+        .map(|file| file.strip_prefix(root).unwrap_or(file))
+        .filter(|file| {
+            !exercises.clone().any(|exercise| {
+                exercise.path == *file
+                    || exercise.hidden_tests.as_deref() == Some(file)
+                    || exercise.watch.iter().any(|watched| file.starts_with(watched))
+            })
+        })
+        .map(|file| {
+            Problem::warning(
+                file,
+                None,
+                "no exercise in info.toml or a track uses this file".to_string(),
+            )
+        })
+        .collect()
+}
+
+// The location of the unknown key or value an error message of the toml
+// crate is about, like "unknown variant `tests`, expected one of ... for key `exercises.mode`"
+fn pinpoint(info_toml: &str, message: &str) -> Option<(usize, usize)> {
+    let quoted = |prefix: &str| message.split(prefix).nth(1)?.split('`').next();
+    if let Some(field) = quoted("unknown field `") {
+        return locate_key(info_toml, field, |_| true, 0).map(|(line, column, _)| (line, column));
+    }
+    let variant = quoted("unknown variant `")?;
+    let key = quoted(" for key `")?.rsplit('.').next()?;
+    locate(info_toml, key, variant, 0)
+}
+
+// The line and column of the value the nth time the key is set to the
+// string, like `name = "variables1"`, skipping comments
+fn locate(info_toml: &str, key: &str, value: &str, nth: usize) -> Option<(usize, usize)> {
+    let quoted = format!("\"{value}\"");
+    locate_key(info_toml, key, |set| set == quoted, nth).map(|(line, _, column)| (line, column))
+}
+
+// The line, the column of the key and the column of the value of the nth
+// time the key is set to a value the predicate accepts, skipping comments
+fn locate_key(
+    info_toml: &str,
+    key: &str,
+    value: impl Fn(&str) -> bool,
+    nth: usize,
+) -> Option<(usize, usize, usize)> {
+    info_toml
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let column = |rest: &str| line.len() - rest.len() + 1;
+            let rest = line.trim_start();
+            let set = rest.strip_prefix(key)?.trim_start().strip_prefix('=')?.trim_start();
+            value(set.trim_end()).then_some((i + 1, column(rest), column(set)))
+        })
+        .nth(nth)
+}
+
+// Every Rust file in the directory and the ones in it
+fn rust_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            rust_files(&path, files);
+        } else if path.extension().is_some_and(|extension| extension == "rs") {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXERCISE: &str = "[[exercises]]\nname = \"compSuccess\"\npath = \"compSuccess.rs\"\nmode = \"compile\"\nhint = \"\"\n";
+
+    #[test]
+    fn test_parse_errors_have_locations() {
+        let file = Path::new("info.toml");
+        let typo = EXERCISE.replace("\"compile\"", "\"tests\"");
+        let problem = parse(file, &typo).err().unwrap();
+        assert_eq!(problem.location, Some((4, 8)));
+        assert!(problem.message.starts_with("unknown variant `tests`"));
+        assert!(!problem.message.contains("at line"));
+
+        let problem = parse(file, &EXERCISE.replace("hint", "hnt")).err().unwrap();
+        assert!(problem.message.starts_with("missing field `hint`"));
+
+        let problem = parse(file, &EXERCISE.replace("\"compSuccess\"", "3")).err().unwrap();
+        assert_eq!(problem.location, Some((2, 8)));
+    }
+
+    fn manifest(path: &str, text: String) -> Manifest {
+        Manifest {
+            path: PathBuf::from(path),
+            list: parse(Path::new(path), &text).unwrap(),
+            text,
+        }
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let category = "[[categories]]\nname = \"intro\"\nrequired = 1\nrquired = 2\n\n";
+        let text = format!("bakend = \"cargo\"\n\n{category}{EXERCISE}tmeout = 3\n\n{EXERCISE}tmeout = 4\n");
+        let problems = unknown_keys(&manifest("info.toml", text));
+        let problems: Vec<String> = problems.iter().map(Problem::to_string).collect();
+        assert_eq!(
+            problems,
+            [
+                "info.toml:1:1: warning: rustlings doesn't know the key `bakend` of the manifest, so it's ignored",
+                "info.toml:6:1: warning: rustlings doesn't know the key `rquired` of the category `intro`, so it's ignored",
+                "info.toml:13:1: warning: rustlings doesn't know the key `tmeout` of the exercise `compSuccess`, so it's ignored",
+                "info.toml:20:1: warning: rustlings doesn't know the key `tmeout` of the exercise `compSuccess`, so it's ignored",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let root = Path::new("tests/fixture/success");
+        let info_toml = fs::read_to_string(root.join("info.toml")).unwrap();
+        assert_eq!(check(&[manifest("info.toml", info_toml)], root), []);
+
+        let missing = format!("{EXERCISE}\n{}", EXERCISE.replace("compSuccess.rs", "missing.rs"));
+        assert_eq!(
+            check(&[manifest("info.toml", missing)], root),
+            [
+                Problem::error(
+                    Path::new("info.toml"),
+                    Some((8, 8)),
+                    "there's more than one exercise named `compSuccess`".to_string()
+                ),
+                Problem::error(
+                    Path::new("info.toml"),
+                    Some((9, 8)),
+                    "the file `missing.rs` of the exercise `compSuccess` doesn't exist".to_string()
+                ),
+            ]
+        );
+
+        // An exercise can't be in a track and one it includes
+        let included = manifest("tracks/basics.toml", EXERCISE.to_string());
+        let including = manifest("tracks/more.toml", format!("include = [\"basics\"]\n\n{EXERCISE}"));
+        assert_eq!(
+            check(&[included, including], root),
+            [
+                Problem::error(
+                    Path::new("tracks/more.toml"),
+                    Some((4, 8)),
+                    "there's more than one exercise named `compSuccess`".to_string()
+                ),
+                Problem::error(
+                    Path::new("tracks/more.toml"),
+                    Some((5, 8)),
+                    "there's more than one exercise at `compSuccess.rs`".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_categories() {
+        let root = Path::new("tests/fixture/success");
+        let category = |name: &str, required: usize| {
+            format!("[[categories]]\nname = \"{name}\"\nrequired = {required}\n\n")
+        };
+        let exercise = EXERCISE.replace("compSuccess.rs", "exercises/intro/compSuccess.rs");
+        let fits = format!("{}{exercise}", category("intro", 1));
+        let problems = check(&[manifest("info.toml", fits)], root);
+        assert!(problems.iter().all(|problem| !problem.message.contains("category")));
+
+        let rubric = format!("{}{}{exercise}", category("intro", 2), category("outro", 1));
+        let problems: Vec<Problem> = check(&[manifest("info.toml", rubric)], root)
+            .into_iter()
+            .filter(|problem| problem.message.contains("category"))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                Problem::error(
+                    Path::new("info.toml"),
+                    Some((2, 8)),
+                    "the category `intro` requires 2 exercises, but has only 1".to_string()
+                ),
+                Problem::error(
+                    Path::new("info.toml"),
+                    Some((6, 8)),
+                    "the category `outro` has no exercises".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unlisted_files() {
+        let root = Path::new("tests/fixture/unlisted");
+        let info_toml = fs::read_to_string(root.join("info.toml")).unwrap();
+        let list = parse(Path::new("info.toml"), &info_toml).unwrap();
+        let problems: Vec<String> = unlisted(list.exercises.iter(), root).iter().map(Problem::to_string).collect();
+        assert_eq!(
+            problems,
+            ["exercises/unlisted.rs: warning: no exercise in info.toml or a track uses this file"]
+        );
+    }
+}
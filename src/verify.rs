@@ -1,18 +1,31 @@
-use crate::exercise::{CompiledExercise, Exercise, Mode, State};
+use crate::exercise::{CompiledExercise, Exceeded, Exercise, ExerciseOutput, Mode, RunOptions, State};
+use crate::explain::annotate;
+use crate::grade::{compile_failure, run_failure, ExerciseResult, FailureKind};
+use crate::progress::ProgressStore;
 use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::env;
+use std::time::Instant;
+
+// Why verifying an exercise failed, along with the output explaining it
+type Failed = (FailureKind, ExerciseOutput);
 
 // Verify that the provided container of Exercise objects
 // can be compiled and run without any failures.
 // Any such failures will be reported to the end user.
 // If the Exercise being verified is a test, the verbose boolean
 // determines whether or not the test harness outputs are displayed.
+// Exercises that pass and are no longer marked as not done are recorded in
+// the progress store. The outcome of every verified exercise is added to
+// `results` if given.
 pub fn verify<'a>(
     exercises: impl IntoIterator<Item = &'a Exercise>,
     progress: (usize, usize),
+    options: &RunOptions,
     verbose: bool,
     success_hints: bool,
+    store: &mut ProgressStore,
+    mut results: Option<&mut Vec<ExerciseResult>>,
 ) -> Result<(), &'a Exercise> {
     let (num_done, total) = progress;
     let bar = ProgressBar::new(total as u64);
@@ -25,16 +38,25 @@ pub fn verify<'a>(
     bar.set_message(format!("({:.1} %)", percentage));
 
     for exercise in exercises {
+        let start = Instant::now();
         let compile_result = match exercise.mode {
-            Mode::Test => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-            Mode::Compile => compile_and_run_interactively(exercise, success_hints),
-            Mode::Clippy => compile_only(exercise, success_hints),
-            Mode::BuildScript => compile_and_test(exercise, RunMode::Interactive, verbose, success_hints),
-
+            Mode::Test => compile_and_test(exercise, options, RunMode::Interactive, verbose, success_hints),
+            Mode::Compile => compile_and_run_interactively(exercise, options, success_hints),
+            Mode::Clippy => compile_only(exercise, options, success_hints),
+            Mode::BuildScript => compile_and_test(exercise, options, RunMode::Interactive, verbose, success_hints),
         };
-        if !compile_result.unwrap_or(false) {
+        let (failure, output) = match compile_result {
+            Ok(true) => (None, ExerciseOutput::default()),
+            Ok(false) => (Some(FailureKind::NotDone), ExerciseOutput::default()),
+            Err((failure, output)) => (Some(failure), output),
+        };
+        if let Some(results) = results.as_deref_mut() {
+            results.push(ExerciseResult::new(exercise, failure, &output, start.elapsed()));
+        }
+        if failure.is_some() {
             return Err(exercise);
         }
+        store.record_done(exercise);
         percentage += 100.0 / total as f32;
         bar.inc(1);
         bar.set_message(format!("({:.1} %)", percentage));
@@ -48,30 +70,30 @@ enum RunMode {
 }
 
 // Compile and run the resulting test harness of the given Exercise
-pub fn test(exercise: &Exercise, verbose: bool) -> Result<(), ()> {
-    compile_and_test(exercise, RunMode::NonInteractive, verbose, false)?;
+pub fn test(exercise: &Exercise, options: &RunOptions, verbose: bool) -> Result<(), ()> {
+    compile_and_test(exercise, options, RunMode::NonInteractive, verbose, false).map_err(|_| ())?;
     Ok(())
 }
 
 // Invoke the rust compiler without running the resulting binary
-fn compile_only(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_only(exercise: &Exercise, options: &RunOptions, success_hints: bool) -> Result<bool, Failed> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
 
-    let _ = compile(exercise, &progress_bar)?;
+    let _ = compile(exercise, options, &progress_bar)?;
     progress_bar.finish_and_clear();
 
     Ok(prompt_for_completion(exercise, None, success_hints))
 }
 
 // Compile the given Exercise and run the resulting binary in an interactive mode
-fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Result<bool, ()> {
+fn compile_and_run_interactively(exercise: &Exercise, options: &RunOptions, success_hints: bool) -> Result<bool, Failed> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Compiling {exercise}..."));
     progress_bar.enable_steady_tick(100);
 
-    let compilation = compile(exercise, &progress_bar)?;
+    let compilation = compile(exercise, options, &progress_bar)?;
 
     progress_bar.set_message(format!("Running {exercise}..."));
     let result = compilation.run();
@@ -79,11 +101,17 @@ fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Re
 
     let output = match result {
         Ok(output) => output,
+        Err(output) if output.exceeded == Some(Exceeded::Cancelled) => {
+            return Err((FailureKind::Cancelled, output))
+        }
         Err(output) => {
-            warn!("Ran {} with errors", exercise);
+            match output.exceeded {
+                Some(exceeded) => warn!("Ran {} with errors, it {}", exercise, exceeded),
+                None => warn!("Ran {} with errors", exercise),
+            }
             println!("{}", output.stdout);
             println!("{}", output.stderr);
-            return Err(());
+            return Err((run_failure(exercise, &output), output));
         }
     };
 
@@ -92,12 +120,18 @@ fn compile_and_run_interactively(exercise: &Exercise, success_hints: bool) -> Re
 
 // Compile the given Exercise as a test harness and display
 // the output if verbose is set to true
-fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, success_hints: bool) -> Result<bool, ()> {
+fn compile_and_test(
+    exercise: &Exercise,
+    options: &RunOptions,
+    run_mode: RunMode,
+    verbose: bool,
+    success_hints: bool,
+) -> Result<bool, Failed> {
     let progress_bar = ProgressBar::new_spinner();
     progress_bar.set_message(format!("Testing {exercise}..."));
     progress_bar.enable_steady_tick(100);
 
-    let compilation = compile(exercise, &progress_bar)?;
+    let compilation = compile(exercise, options, &progress_bar)?;
     let result = compilation.run();
     progress_bar.finish_and_clear();
 
@@ -112,13 +146,23 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
                 Ok(true)
             }
         }
+        Err(output) if output.exceeded == Some(Exceeded::Cancelled) => {
+            Err((FailureKind::Cancelled, output))
+        }
         Err(output) => {
-            warn!(
-                "Testing of {} failed! Please try again. Here's the output:",
-                exercise
-            );
+            match output.exceeded {
+                Some(exceeded) => warn!(
+                    "Testing of {} failed, it {}! Please try again. Here's the output:",
+                    exercise,
+                    exceeded
+                ),
+                None => warn!(
+                    "Testing of {} failed! Please try again. Here's the output:",
+                    exercise
+                ),
+            }
             println!("{}", output.stdout);
-            Err(())
+            Err((run_failure(exercise, &output), output))
         }
     }
 }
@@ -127,20 +171,26 @@ fn compile_and_test(exercise: &Exercise, run_mode: RunMode, verbose: bool, succe
 // about the state of the compilation
 fn compile<'a, 'b>(
     exercise: &'a Exercise,
+    options: &'a RunOptions,
     progress_bar: &'b ProgressBar,
-) -> Result<CompiledExercise<'a>, ()> {
-    let compilation_result = exercise.compile();
+) -> Result<CompiledExercise<'a>, Failed> {
+    let compilation_result = exercise.compile(options);
 
     match compilation_result {
         Ok(compilation) => Ok(compilation),
+        // Nobody is waiting for the result anymore
+        Err(output) if output.exceeded == Some(Exceeded::Cancelled) => {
+            progress_bar.finish_and_clear();
+            Err((FailureKind::Cancelled, output))
+        }
         Err(output) => {
             progress_bar.finish_and_clear();
             warn!(
                 "Compiling of {} failed! Please try again. Here's the output:",
                 exercise
             );
-            println!("{}", output.stderr);
-            Err(())
+            println!("{}", annotate(exercise, &output, true));
+            Err((compile_failure(exercise, &output), output))
         }
     }
 }
@@ -0,0 +1,533 @@
+use crate::exercise::{Exercise, RunOptions};
+use crate::explain::explain;
+use crate::grade::{ExerciseResult, FailureKind};
+use crate::line_editor::{EditingMode, LineEditor};
+use crate::list::{list, ListArgs};
+use crate::pristine::reset;
+use crate::progress::ProgressStore;
+use crate::run::run;
+use crate::solution::print_solution;
+use crate::tui;
+use crate::verify::verify;
+use crate::watcher::ExerciseWatcher;
+use std::io::{self, IsTerminal};
+use std::iter;
+use std::process::Command;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// How watch mode ended
+pub enum WatchStatus {
+    Finished,
+    Unfinished,
+}
+
+// A command typed into the watch shell that needs the state of watch mode
+pub enum ShellCommand {
+    // Go to the exercise with the given name
+    Goto(String),
+    // Go to the exercise following the current one
+    Next,
+    // Go to the exercise before the current one
+    Prev,
+    // Leave the current exercise for now and go to the next pending one
+    Skip,
+    // Toggle only re-verifying the current exercise on save
+    Focus,
+    // Run the given or current exercise and show its output
+    Run(Option<String>),
+    // Reset the given or current exercise to its original state
+    Reset(Option<String>),
+    // Show the progress of all exercises
+    List,
+    // Show the reference solution of the given or current exercise
+    Solution(Option<String>),
+    // Explain the error codes of the last failure
+    Explain,
+    Quit,
+}
+
+// The commands of the watch shell, the first ones taking the name of an exercise
+const COMMANDS: [&str; 14] = [
+    "goto", "run", "reset", "solution", "hint", "next", "prev", "skip", "focus", "list",
+    "explain", "clear", "quit", "help",
+];
+const NAMED_COMMANDS: usize = 4;
+
+// What the `help` command shows, in the shell as well as the full-screen UI
+pub const HELP: &str = "Commands available to you in watch mode:
+  hint            - prints the current exercise's hint
+  goto <name>     - goes to the exercise with the given name
+  next            - goes to the next exercise
+  prev            - goes to the previous exercise
+  skip            - leaves the current exercise for later
+  focus           - toggles only re-evaluating the current exercise
+  run [name]      - runs the current or given exercise and shows its output
+  reset [name]    - resets the current or given exercise
+  solution [name] - shows the solution of the current or given exercise
+  list            - lists the exercises along with their status
+  explain         - explains the error codes of the last failure
+  clear           - clears the screen
+  quit            - quits watch mode
+  !<cmd>          - executes a command, like `!rustc --explain E0381`
+  help            - displays this help message
+
+Watch mode automatically re-evaluates the exercise you edit,
+followed by the pending exercises after it.
+In focus mode, only the current exercise is re-evaluated.
+";
+
+pub fn parse_command(input: &str) -> Option<ShellCommand> {
+    let (command, name) = match input.split_once(' ') {
+        Some((command, name)) => (command, Some(name.trim().to_string())),
+        None => (input, None),
+    };
+    let command = match (command, name) {
+        ("goto", Some(name)) => ShellCommand::Goto(name),
+        ("run", name) => ShellCommand::Run(name),
+        ("reset", name) => ShellCommand::Reset(name),
+        ("solution", name) => ShellCommand::Solution(name),
+        ("next", None) => ShellCommand::Next,
+        ("prev", None) => ShellCommand::Prev,
+        ("skip", None) => ShellCommand::Skip,
+        ("focus", None) => ShellCommand::Focus,
+        ("list", None) => ShellCommand::List,
+        ("explain", None) => ShellCommand::Explain,
+        ("quit", None) => ShellCommand::Quit,
+        _ => return None,
+    };
+    Some(command)
+}
+
+// The completions of a partially typed command, as whole lines
+pub fn complete(line: &str, names: &[String]) -> Vec<String> {
+    match line.split_once(' ') {
+        None => COMMANDS
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| command.starts_with(line))
+            .map(|(i, command)| {
+                if i < NAMED_COMMANDS {
+                    format!("{command} ")
+                } else {
+                    command.to_string()
+                }
+            })
+            .collect(),
+        Some((command, name)) if COMMANDS[..NAMED_COMMANDS].contains(&command) => names
+            .iter()
+            .filter(|candidate| candidate.starts_with(name))
+            .map(|candidate| format!("{command} {candidate}"))
+            .collect(),
+        Some(_) => Vec::new(),
+    }
+}
+
+fn spawn_watch_shell(
+    exercises: &[Exercise],
+    current_hint: &Arc<Mutex<Option<String>>>,
+    commands: Sender<ShellCommand>,
+    editing: bool,
+) {
+    let current_hint = Arc::clone(current_hint);
+    let names: Vec<String> = exercises.iter().map(|e| e.name.clone()).collect();
+    let mut editor = LineEditor::new(editing, move |line: &str| complete(line, &names));
+    thread::spawn(move || loop {
+        match editor.read_line() {
+            // The input ended
+            Ok(None) => {
+                let _ = commands.send(ShellCommand::Quit);
+                return;
+            }
+            Ok(Some(input)) => {
+                let input = input.trim();
+                if let Some(command) = parse_command(input) {
+                    let quit = matches!(command, ShellCommand::Quit);
+                    if commands.send(command).is_err() || quit {
+                        return;
+                    }
+                } else if input == "hint" {
+                    if let Some(hint) = &*current_hint.lock().unwrap() {
+                        println!("{hint}");
+                    }
+                } else if input == "clear" {
+                    println!("\x1B[2J\x1B[1;1H");
+                } else if input.eq("help") {
+                    print!("{HELP}");
+                    if editing {
+                        println!("Press tab to complete commands and exercise names, and the arrow keys for earlier commands.");
+                    }
+                } else if let Some(cmd) = input.strip_prefix('!') {
+                    let parts: Vec<&str> = cmd.split_whitespace().collect();
+                    if parts.is_empty() {
+                        println!("no command provided");
+                    } else if let Err(e) = Command::new(parts[0]).args(&parts[1..]).status() {
+                        println!("failed to execute command `{}`: {}", cmd, e);
+                    }
+                } else if !input.is_empty() {
+                    println!("unknown command: {input}");
+                }
+            }
+            Err(error) => println!("error reading command: {error}"),
+        }
+    });
+}
+
+// The first pending exercise at or after `from`, wrapping around to the start
+pub fn next_pending(exercises: &[Exercise], store: &ProgressStore, from: usize) -> Option<usize> {
+    let from = from.min(exercises.len());
+    (from..exercises.len())
+        .chain(0..from)
+        .find(|&i| !store.is_done(&exercises[i]))
+}
+
+// The exercise following the current one, staying at the last one
+pub fn next_index(exercises: &[Exercise], current: usize) -> usize {
+    (current + 1).min(exercises.len().saturating_sub(1))
+}
+
+// Watch mode printing its results line by line, used when the output isn't a terminal
+struct LineWatch<'a> {
+    exercises: &'a [Exercise],
+    store: &'a mut ProgressStore,
+    // Cancelled by the watcher when a newer save makes the running check pointless
+    options: &'a RunOptions,
+    commands: Receiver<ShellCommand>,
+    // Commands typed while an exercise was being verified, handled once it's done
+    queued: Vec<ShellCommand>,
+    // Whether the user asked to quit
+    quit: bool,
+    verbose: bool,
+    success_hints: bool,
+    // The exercise being worked on
+    current: usize,
+    // Whether only the current exercise is re-verified on save
+    focus: bool,
+    current_hint: Arc<Mutex<Option<String>>>,
+    // The output of the last exercise that failed to verify
+    last_failure: String,
+}
+
+// Watch the exercises, cancelling the running check through `options` on every save
+pub fn watch(
+    exercises: &[Exercise],
+    store: &mut ProgressStore,
+    options: &RunOptions,
+    verbose: bool,
+    success_hints: bool,
+) -> notify::Result<WatchStatus> {
+    let watcher = ExerciseWatcher::new(exercises, options.cancel.clone())?;
+
+    // The full-screen UI needs a terminal, anything else gets plain lines
+    if io::stdout().is_terminal() {
+        return tui::watch(exercises, store, options, &watcher, success_hints);
+    }
+
+    // The shell runs from the start, so that quitting doesn't have to wait
+    // for the first verification
+    let (commands_tx, commands) = channel();
+    let mut line_watch = LineWatch {
+        exercises,
+        store,
+        options,
+        commands,
+        queued: Vec::new(),
+        quit: false,
+        verbose,
+        success_hints,
+        current: 0,
+        focus: false,
+        current_hint: Arc::new(Mutex::new(None)),
+        last_failure: String::new(),
+    };
+    // Restores the terminal when watch mode ends
+    let editing = EditingMode::enable();
+    spawn_watch_shell(exercises, &line_watch.current_hint, commands_tx, editing.is_some());
+    clear_screen();
+    if line_watch.verify_all() {
+        return Ok(WatchStatus::Finished);
+    }
+    if !line_watch.quit {
+        println!("Welcome to watch mode! You can type 'help' to get an overview of the commands you can use here.");
+    }
+    line_watch.run(&watcher)
+}
+
+/* Clears the terminal with an ANSI escape code.
+Works in UNIX and newer Windows terminals. */
+fn clear_screen() {
+    println!("\x1Bc");
+}
+
+impl LineWatch<'_> {
+    fn run(&mut self, watcher: &ExerciseWatcher) -> notify::Result<WatchStatus> {
+        loop {
+            if self.quit {
+                println!("Bye!");
+                return Ok(WatchStatus::Unfinished);
+            }
+            watcher.set_focus(self.focus.then_some(self.current));
+            match watcher.recv_timeout(Duration::from_millis(100)) {
+                Ok(affected) => {
+                    if self.focus {
+                        if affected.contains(&self.current) {
+                            clear_screen();
+                            self.verify_current();
+                        }
+                    } else if let Some(&first) = affected.first() {
+                        clear_screen();
+                        if self.verify_from(first) {
+                            return Ok(WatchStatus::Finished);
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    // the timeout expired, just check for commands below then loop again
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    println!("Watching the exercises stopped unexpectedly");
+                    return Ok(WatchStatus::Unfinished);
+                }
+            }
+
+            let commands: Vec<ShellCommand> =
+                self.queued.drain(..).chain(self.commands.try_iter()).collect();
+            for command in commands {
+                if self.quit {
+                    break;
+                }
+                match command {
+                    ShellCommand::Quit => {
+                        self.quit = true;
+                        continue;
+                    }
+                    ShellCommand::Focus => {
+                        self.focus = !self.focus;
+                        if self.focus {
+                            println!("Focusing on {}", self.exercises[self.current]);
+                        } else {
+                            println!("No longer focusing on {}", self.exercises[self.current]);
+                        }
+                        continue;
+                    }
+                    ShellCommand::Goto(name) => match self.find(Some(name)) {
+                        Some(index) => self.current = index,
+                        None => continue,
+                    },
+                    ShellCommand::Run(name) => {
+                        if let Some(index) = self.find(name) {
+                            self.run_exercise(index);
+                        }
+                        continue;
+                    }
+                    ShellCommand::Reset(name) => {
+                        // Saving the original exercise gets it verified again
+                        if let Some(exercise) = self.find(name).map(|i| &self.exercises[i]) {
+                            match reset(exercise) {
+                                Ok(Some(backup)) => {
+                                    println!("Reset {exercise}, your version is backed up in {}", backup.display())
+                                }
+                                Ok(None) => println!("{exercise} is already in its original state"),
+                                Err(e) => println!("{e}"),
+                            }
+                        }
+                        continue;
+                    }
+                    ShellCommand::List => {
+                        let _ = list(self.exercises, self.store, &ListArgs::default());
+                        continue;
+                    }
+                    ShellCommand::Solution(name) => {
+                        if let Some(exercise) = self.find(name).map(|i| &self.exercises[i]) {
+                            if self.store.is_done(exercise) {
+                                let _ = print_solution(exercise, false);
+                            } else {
+                                println!("Solve {} first to see its solution!", exercise.name);
+                            }
+                        }
+                        continue;
+                    }
+                    ShellCommand::Explain => {
+                        if !explain(&self.last_failure) {
+                            println!("The last failure didn't have any error codes to explain");
+                        }
+                        continue;
+                    }
+                    ShellCommand::Next => self.current = next_index(self.exercises, self.current),
+                    ShellCommand::Prev => self.current = self.current.saturating_sub(1),
+                    ShellCommand::Skip => {
+                        let next = next_pending(self.exercises, self.store, self.current + 1);
+                        self.current = next.unwrap_or(self.current);
+                    }
+                }
+                clear_screen();
+                self.verify_current();
+            }
+        }
+    }
+
+    // Verify all exercises in order, stopping at the first that isn't done.
+    // Returns whether all of them are done.
+    fn verify_all(&mut self) -> bool {
+        let result = self.verify_exercises(self.exercises, 0);
+        self.update_current(result)
+    }
+
+    // Verify the given exercise followed by the pending exercises after it,
+    // stopping at the first that isn't done. Returns whether all are done.
+    fn verify_from(&mut self, start: usize) -> bool {
+        let pending: Vec<&Exercise> = iter::once(start)
+            .chain((start + 1..self.exercises.len()).chain(0..start))
+            .map(|i| &self.exercises[i])
+            .filter(|e| e.name == self.exercises[start].name || !self.store.is_done(e))
+            .collect();
+        let num_done = self.exercises.iter().filter(|e| self.store.is_done(e)).count();
+        let result = self.verify_exercises(pending, num_done);
+        self.update_current(result)
+    }
+
+    // Verify only the current exercise, leaving the rest alone
+    fn verify_current(&mut self) {
+        let exercise = &self.exercises[self.current];
+        let num_done = self.exercises.iter().filter(|e| self.store.is_done(e)).count();
+        let _ = self.verify_exercises(iter::once(exercise), num_done);
+        *self.current_hint.lock().unwrap() = Some(exercise.hint.clone());
+    }
+
+    // Verify the exercises in the background, remembering the output of the
+    // one that failed for `explain`
+    fn verify_exercises<'e>(
+        &mut self,
+        exercises: impl IntoIterator<Item = &'e Exercise> + Send,
+        num_done: usize,
+    ) -> Result<(), &'e Exercise> {
+        let (total, options, verbose, success_hints) =
+            (self.exercises.len(), self.options, self.verbose, self.success_hints);
+        let (result, results) = self.in_background(|store| {
+            let mut results = Vec::new();
+            let result = verify(exercises, (num_done, total), options, verbose, success_hints, store, Some(&mut results));
+            (result, results)
+        });
+        self.remember_failure(&results);
+        result
+    }
+
+    fn remember_failure(&mut self, results: &[ExerciseResult]) {
+        let failed = results
+            .last()
+            .filter(|result| result.failure.is_some_and(|failure| failure != FailureKind::Cancelled));
+        if let Some(failed) = failed {
+            self.last_failure = format!("{}\n{}", failed.stdout, failed.stderr);
+        }
+    }
+
+    // Run the exercise in the background and show its output
+    fn run_exercise(&mut self, index: usize) {
+        let exercise = &self.exercises[index];
+        let (options, verbose) = (self.options, self.verbose);
+        self.in_background(|store| {
+            if run(exercise, options, verbose).is_ok() && exercise.looks_done() {
+                store.record_done(exercise);
+            }
+        });
+    }
+
+    // The exercise with the given name, or the current one without a name
+    fn find(&self, name: Option<String>) -> Option<usize> {
+        let name = match name {
+            Some(name) => name,
+            None => return Some(self.current),
+        };
+        let index = self.exercises.iter().position(|e| e.name == name);
+        if index.is_none() {
+            println!("No exercise found for '{name}'!");
+        }
+        index
+    }
+
+    // Run the verification on a worker thread while listening to the shell, so
+    // that quitting stops it right away instead of waiting for it to finish.
+    // Other commands are handled once it's done.
+    fn in_background<T: Send>(&mut self, verification: impl FnOnce(&mut ProgressStore) -> T + Send) -> T {
+        self.options.cancel.reset();
+        let store = &mut *self.store;
+        thread::scope(|scope| {
+            let worker = scope.spawn(move || verification(store));
+            while !worker.is_finished() {
+                match self.commands.recv_timeout(Duration::from_millis(100)) {
+                    Ok(ShellCommand::Quit) => {
+                        self.quit = true;
+                        self.options.cancel.cancel();
+                    }
+                    Ok(command) => self.queued.push(command),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // The shell is gone, just wait for the verification
+                    Err(RecvTimeoutError::Disconnected) => thread::sleep(Duration::from_millis(100)),
+                }
+            }
+            worker.join().unwrap()
+        })
+    }
+
+    fn update_current(&mut self, result: Result<(), &Exercise>) -> bool {
+        match result {
+            Ok(()) => true,
+            // A newer save is about to be verified instead
+            Err(_) if self.options.cancel.reset() => false,
+            Err(failed) => {
+                self.current = self
+                    .exercises
+                    .iter()
+                    .position(|e| e.name == failed.name)
+                    .unwrap_or(self.current);
+                *self.current_hint.lock().unwrap() = Some(failed.hint.clone());
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    #[test]
+    fn test_complete() {
+        let names = vec!["move_semantics1".to_string(), "move_semantics2".to_string(), "intro1".to_string()];
+        assert_eq!(complete("go", &names), ["goto "]);
+        assert_eq!(complete("re", &names), ["reset "]);
+        assert_eq!(complete("s", &names), ["solution ", "skip"]);
+        assert_eq!(complete("goto move", &names), ["goto move_semantics1", "goto move_semantics2"]);
+        assert_eq!(complete("run in", &names), ["run intro1"]);
+        assert!(complete("next in", &names).is_empty());
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert!(matches!(parse_command("run"), Some(ShellCommand::Run(None))));
+        assert!(matches!(parse_command("run intro1"), Some(ShellCommand::Run(Some(name))) if name == "intro1"));
+        assert!(parse_command("goto").is_none());
+        assert!(matches!(parse_command("list"), Some(ShellCommand::List)));
+        assert!(parse_command("list all").is_none());
+    }
+
+    #[test]
+    fn test_moving_on() {
+        let store = ProgressStore::default();
+        let exercises = vec![
+            Exercise::for_test("intro1", "missing/intro1.rs", Mode::Compile),
+            Exercise::for_test("intro2", "missing/intro2.rs", Mode::Compile),
+        ];
+        assert_eq!(next_pending(&exercises, &store, 1), Some(1));
+        assert_eq!(next_pending(&exercises, &store, 2), Some(0));
+        assert_eq!(next_index(&exercises, 0), 1);
+        assert_eq!(next_index(&exercises, 1), 1);
+
+        // A track may not have any exercises yet
+        assert_eq!(next_pending(&[], &store, 1), None);
+        assert_eq!(next_index(&[], 0), 0);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::exercise::{Cancel, Exercise};
+use notify::event::{EventKind, ModifyKind, RenameMode};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The directory watched for changes to the exercises
+const EXERCISES_DIR: &str = "exercises";
+
+// How long a file has to be left alone before its change is reported.
+// Editors often write a file several times in a row when saving it.
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+// Watches the files of the exercises and reports which exercises changed.
+// Bursts of events for the same file are coalesced into a single change,
+// files that editors rename into place count as saved, and the temporary
+// files they write along the way are ignored. As soon as a relevant file
+// is touched, `cancel` is set, since the running check is about to be
+// outdated.
+pub struct ExerciseWatcher {
+    // Stops watching once dropped
+    _watcher: RecommendedWatcher,
+    changes: Receiver<Vec<usize>>,
+    focus: Arc<Mutex<Option<usize>>>,
+}
+
+impl ExerciseWatcher {
+    pub fn new(exercises: &[Exercise], cancel: Cancel) -> notify::Result<Self> {
+        let (events_tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(events_tx)?;
+        let exercises_dir = Path::new(EXERCISES_DIR).canonicalize()?;
+        watcher.watch(&exercises_dir, RecursiveMode::Recursive)?;
+        // Paths the exercises declare outside of the exercises directory.
+        // Files are watched through their directory, so that replacing them
+        // with a renamed file doesn't end the watch.
+        for path in exercises.iter().flat_map(|e| &e.watch) {
+            let path = match path.canonicalize() {
+                Ok(path) if !path.starts_with(&exercises_dir) => path,
+                _ => continue,
+            };
+            match path.parent() {
+                Some(dir) if path.is_file() => watcher.watch(dir, RecursiveMode::NonRecursive)?,
+                _ => watcher.watch(&path, RecursiveMode::Recursive)?,
+            }
+        }
+
+        let (changes_tx, changes) = channel();
+        let focus = Arc::new(Mutex::new(None));
+        let coalescer = Coalescer {
+            exercises: exercises.to_vec(),
+            focus: Arc::clone(&focus),
+            cancel,
+            changes: changes_tx,
+            pending: HashMap::new(),
+        };
+        thread::spawn(move || coalescer.run(&events));
+
+        Ok(ExerciseWatcher {
+            _watcher: watcher,
+            changes,
+            focus,
+        })
+    }
+
+    // Only report changes affecting the given exercise, or any exercise when
+    // there's no focus
+    pub fn set_focus(&self, focus: Option<usize>) {
+        *self.focus.lock().unwrap() = focus;
+    }
+
+    // Wait for the next change, returning the affected exercises in order
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Vec<usize>, RecvTimeoutError> {
+        self.changes.recv_timeout(timeout)
+    }
+
+    // The changes that happened since the last call, without waiting
+    pub fn try_iter(&self) -> impl Iterator<Item = Vec<usize>> + '_ {
+        self.changes.try_iter()
+    }
+}
+
+// Turns the raw events of the watcher into changes of the exercises
+struct Coalescer {
+    exercises: Vec<Exercise>,
+    focus: Arc<Mutex<Option<usize>>>,
+    cancel: Cancel,
+    changes: Sender<Vec<usize>>,
+    // The files that changed, along with when they were last touched
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl Coalescer {
+    fn run(mut self, events: &Receiver<notify::Result<Event>>) {
+        loop {
+            let now = Instant::now();
+            let wait = self
+                .pending
+                .values()
+                .map(|touched| (*touched + SETTLE_TIME).saturating_duration_since(now))
+                .min()
+                .unwrap_or(Duration::from_secs(60));
+            match events.recv_timeout(wait) {
+                Ok(Ok(event)) => self.record(event),
+                // Errors for single paths don't stop the rest from being watched
+                Ok(Err(_)) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            let now = Instant::now();
+            let settled: Vec<PathBuf> = self
+                .pending
+                .iter()
+                .filter(|(_, touched)| now >= **touched + SETTLE_TIME)
+                .map(|(path, _)| path.clone())
+                .collect();
+            for path in settled {
+                self.pending.remove(&path);
+                let affected = self.affected(&path);
+                if !affected.is_empty() && self.changes.send(affected).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn record(&mut self, event: Event) {
+        let paths = match event.kind {
+            // The old name of a renamed file is gone, only its new name matters
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => return,
+            EventKind::Modify(ModifyKind::Metadata(_)) => return,
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                event.paths.into_iter().skip(1).collect()
+            }
+            EventKind::Create(_) | EventKind::Modify(_) => event.paths,
+            _ => return,
+        };
+        for path in paths.into_iter().filter(|path| !is_temporary(path)) {
+            if !self.affected(&path).is_empty() {
+                self.cancel.cancel();
+            }
+            self.pending.insert(path, Instant::now());
+        }
+    }
+
+    // The exercises affected by a change to the file, taking the focus into account.
+    // Files that are already gone again don't affect anything.
+    fn affected(&self, path: &Path) -> Vec<usize> {
+        let path = match path.canonicalize() {
+            Ok(path) => path,
+            Err(_) => return Vec::new(),
+        };
+        let focus = *self.focus.lock().unwrap();
+        (0..self.exercises.len())
+            .filter(|&i| focus.is_none() || focus == Some(i))
+            .filter(|&i| self.exercises[i].is_affected_by(&path))
+            .collect()
+    }
+}
+
+// Whether the file is one of the temporary files editors write while saving,
+// like vim's swap files and `4913`, emacs' lock files or JetBrains' safe writes
+fn is_temporary(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name,
+        None => return true,
+    };
+    let extension = path.extension().and_then(|extension| extension.to_str());
+    name == "4913"
+        || name.ends_with('~')
+        || name.starts_with(".#")
+        || (name.starts_with('#') && name.ends_with('#'))
+        || name.ends_with("___jb_tmp___")
+        || name.ends_with("___jb_old___")
+        || matches!(extension, Some("swp" | "swo" | "swx" | "tmp" | "bak"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_temporary_files() {
+        for temporary in [
+            "exercises/intro/.intro1.rs.swp",
+            "exercises/intro/4913",
+            "exercises/intro/intro1.rs~",
+            "exercises/intro/.#intro1.rs",
+            "exercises/intro/#intro1.rs#",
+            "exercises/intro/intro1.rs___jb_tmp___",
+        ] {
+            assert!(is_temporary(Path::new(temporary)), "{temporary}");
+        }
+        assert!(!is_temporary(Path::new("exercises/intro/intro1.rs")));
+        assert!(!is_temporary(Path::new("exercises/tests/build.rs")));
+    }
+}
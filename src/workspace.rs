@@ -0,0 +1,245 @@
+use crate::exercise::{absolute, Backend, Exercise, HIDDEN_TESTS_MODULE};
+use crate::solution::solution_path;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::value::{Table, Value};
+
+// The cargo workspace the exercises are compiled in with `backend = "cargo"`.
+// It's kept between runs, so that dependencies and unchanged exercises
+// don't have to be compiled again.
+pub const WORKSPACE_DIR: &str = ".rustlings/workspace";
+
+// Crates vendored with `rustlings vendor`, used for building offline
+pub const VENDOR_DIR: &str = "vendor";
+
+const GENERATED: &str = "# Generated by rustlings from info.toml, changes are overwritten\n";
+
+// The name of the binary target compiling the source file, derived from its path
+pub fn bin_name(source: &Path) -> String {
+    let relative = source
+        .strip_prefix(absolute(Path::new(".")))
+        .unwrap_or(source)
+        .with_extension("");
+    let name: String = relative
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    name.trim_start_matches('_').to_string()
+}
+
+// Where the exercise combined with its hidden tests is written to
+pub fn hidden_tests_source(exercise: &Exercise) -> PathBuf {
+    Path::new(WORKSPACE_DIR)
+        .join("hidden")
+        .join(format!("{}.rs", exercise.name))
+}
+
+// The exercise with its hidden tests appended as a module
+pub fn with_hidden_tests(exercise: &Exercise, hidden_tests: &Path) -> io::Result<String> {
+    let source = fs::read_to_string(&exercise.path)?;
+    Ok(format!(
+        "{source}\n#[cfg(test)]\n#[path = {:?}]\nmod {HIDDEN_TESTS_MODULE};\n",
+        absolute(hidden_tests).display().to_string()
+    ))
+}
+
+// Write the file unless it already has the contents, which would make
+// cargo compile it again
+pub fn write_if_changed(path: &Path, contents: &str) -> io::Result<()> {
+    if fs::read_to_string(path).is_ok_and(|current| current == contents) {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
+
+// Whether crates are built from the vendored sources, without a network
+pub fn is_vendored() -> bool {
+    Path::new(VENDOR_DIR).is_dir()
+}
+
+// Generate the workspace for the exercises compiled with cargo. It has a
+// single package with a binary target for every exercise, its solution and
+// the exercise combined with its hidden tests, along with the dependencies
+// of all exercises.
+pub fn generate(exercises: &[Exercise], backend: Backend) -> Result<(), String> {
+    let exercises: Vec<&Exercise> = exercises.iter().filter(|e| e.uses_workspace(backend)).collect();
+    let dir = Path::new(WORKSPACE_DIR);
+    let mut sources = Vec::new();
+    for exercise in &exercises {
+        sources.push(absolute(&exercise.path));
+        let solution = solution_path(exercise);
+        if solution.exists() {
+            sources.push(absolute(&solution));
+        }
+        if let Some(hidden_tests) = &exercise.hidden_tests {
+            let source = hidden_tests_source(exercise);
+            with_hidden_tests(exercise, hidden_tests)
+                .and_then(|combined| write_if_changed(&source, &combined))
+                .map_err(|e| format!("Failed to add the hidden tests to {exercise}: {e}"))?;
+            sources.push(absolute(&source));
+        }
+    }
+    let manifest = manifest(&sources, &dependencies(&exercises)?);
+    write_if_changed(&dir.join("Cargo.toml"), &manifest)
+        .map_err(|e| format!("Failed to write {}: {e}", dir.join("Cargo.toml").display()))?;
+
+    // Cargo picks up its configuration from the directory it's run in
+    let config = dir.join(".cargo").join("config.toml");
+    let written = if is_vendored() {
+        write_if_changed(&config, &vendor_config(&absolute(Path::new(VENDOR_DIR))))
+    } else {
+        fs::remove_file(&config).or_else(|e| match e.kind() {
+            io::ErrorKind::NotFound => Ok(()),
+            _ => Err(e),
+        })
+    };
+    written.map_err(|e| format!("Failed to write {}: {e}", config.display()))
+}
+
+// The dependencies of all exercises, which have to agree on the crates they share
+fn dependencies(exercises: &[&Exercise]) -> Result<Table, String> {
+    let mut dependencies: BTreeMap<&str, (&Value, &Exercise)> = BTreeMap::new();
+    for exercise in exercises {
+        for (name, spec) in &exercise.dependencies {
+            match dependencies.get(name.as_str()) {
+                Some((other, declared_by)) if *other != spec => {
+                    return Err(format!(
+                        "{exercise} and {declared_by} depend on different versions of `{name}`"
+                    ))
+                }
+                _ => {
+                    dependencies.insert(name, (spec, exercise));
+                }
+            }
+        }
+    }
+    Ok(dependencies
+        .into_iter()
+        .map(|(name, (spec, _))| (name.to_string(), spec.clone()))
+        .collect())
+}
+
+fn manifest(sources: &[PathBuf], dependencies: &Table) -> String {
+    let mut manifest = format!(
+        r#"{GENERATED}[package]
+name = "exercises"
+version = "0.0.0"
+edition = "2021"
+publish = false
+autobins = false
+autoexamples = false
+autotests = false
+autobenches = false
+
+[workspace]
+"#
+    );
+    for source in sources {
+        manifest.push_str(&format!(
+            "\n[[bin]]\nname = \"{}\"\npath = {:?}\n",
+            bin_name(source),
+            source.display().to_string()
+        ));
+    }
+    manifest.push_str("\n[dependencies]\n");
+    manifest.push_str(&dependencies_table(dependencies));
+    manifest
+}
+
+// The entries of a `[dependencies]` table, one crate per line. Paths of
+// local crates are relative to info.toml, which isn't where the manifest is.
+pub fn dependencies_table(dependencies: &Table) -> String {
+    dependencies
+        .iter()
+        .map(|(name, spec)| {
+            let mut spec = spec.clone();
+            if let Some(Value::String(path)) = spec.get_mut("path") {
+                *path = absolute(Path::new(path.as_str())).display().to_string();
+            }
+            format!("{name} = {}\n", inline(&spec))
+        })
+        .collect()
+}
+
+// The value as inline TOML, which is how dependencies are usually written
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Table(table) => {
+            let entries: Vec<String> = table
+                .iter()
+                .map(|(key, value)| format!("{key} = {}", inline(value)))
+                .collect();
+            format!("{{ {} }}", entries.join(", "))
+        }
+        Value::Array(values) => {
+            let values: Vec<String> = values.iter().map(inline).collect();
+            format!("[{}]", values.join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+fn vendor_config(vendor: &Path) -> String {
+    format!(
+        r#"{GENERATED}[source.crates-io]
+replace-with = "vendored-sources"
+
+[source.vendored-sources]
+directory = {:?}
+"#,
+        vendor.display().to_string()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exercise::Mode;
+
+    #[test]
+    fn test_bin_name() {
+        let source = absolute(Path::new("exercises/intro/intro1.rs"));
+        assert_eq!(bin_name(&source), "exercises_intro_intro1");
+        let hidden = absolute(&Path::new(WORKSPACE_DIR).join("hidden/tests1.rs"));
+        assert_eq!(bin_name(&hidden), "rustlings_workspace_hidden_tests1");
+    }
+
+    fn table(toml: &str) -> Table {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn test_manifest() {
+        let dependencies = table("rand = \"0.8\"\nserde = { version = \"1\", features = [\"derive\"] }");
+        let sources = [absolute(Path::new("exercises/intro/intro1.rs"))];
+        let manifest = manifest(&sources, &dependencies);
+        assert!(manifest.contains("[[bin]]\nname = \"exercises_intro_intro1\"\npath = "));
+        assert!(manifest.contains("rand = \"0.8\"\n"));
+        assert!(manifest.contains("serde = { features = [\"derive\"], version = \"1\" }\n"));
+        assert!(toml::from_str::<Value>(&manifest).is_ok());
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let exercise = |name: &str, dependencies: &str| Exercise {
+            dependencies: table(dependencies),
+            ..Exercise::for_test(name, format!("exercises/crates/{name}.rs"), Mode::Compile)
+        };
+        let rand = exercise("crates1", "rand = \"0.8\"");
+        let serde = exercise("crates2", "rand = \"0.8\"\nserde = \"1\"");
+        let merged = dependencies(&[&rand, &serde]).unwrap();
+        assert_eq!(merged, table("rand = \"0.8\"\nserde = \"1\""));
+
+        let other_rand = exercise("crates3", "rand = \"0.7\"");
+        assert_eq!(
+            dependencies(&[&rand, &other_rand]).unwrap_err(),
+            "exercises/crates/crates3.rs and exercises/crates/crates1.rs depend on different versions of `rand`"
+        );
+    }
+}
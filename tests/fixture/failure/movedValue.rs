@@ -0,0 +1,5 @@
+fn main() {
+    let vec0 = vec![22, 44, 66];
+    let vec1 = vec0;
+    println!("{:?} {:?}", vec0, vec1);
+}
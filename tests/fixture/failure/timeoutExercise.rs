@@ -0,0 +1,9 @@
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    loop {
+        println!("Still running...");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,16 @@
+fn add(a: i32, b: i32) -> i32 {
+    if a < 0 || b < 0 {
+        return 0;
+    }
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        assert_eq!(add(1, 2), 3);
+    }
+}
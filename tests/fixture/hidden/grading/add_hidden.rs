@@ -0,0 +1,6 @@
+use super::*;
+
+#[test]
+fn adds_zero() {
+    assert_eq!(add(0, 2), 2);
+}
@@ -0,0 +1,3 @@
+mod helpers;
+
+fn main() {}
@@ -3,7 +3,23 @@ use glob::glob;
 use predicates::boolean::PredicateBooleanExt;
 use std::fs::File;
 use std::io::Read;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// A new empty directory for a test, unique to it so tests can run in parallel
+// and next to other runs of the test suite
+fn temp_dir(name: &str) -> PathBuf {
+    static DIRS: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "rustlings_{name}_{}_{}",
+        process::id(),
+        DIRS.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
 
 #[test]
 fn runs_without_arguments() {
@@ -60,6 +76,18 @@ fn run_single_compile_failure() {
         .code(1);
 }
 
+#[test]
+fn run_compile_failure_explains_error_codes() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "movedValue"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("E0382 at movedValue.rs:4:27"))
+        .stdout(predicates::str::contains("moved elsewhere"));
+}
+
 #[test]
 fn run_single_test_success() {
     Command::cargo_bin("rustlings")
@@ -70,6 +98,38 @@ fn run_single_test_success() {
         .success();
 }
 
+#[test]
+fn run_in_cargo_workspace() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "testSuccess"])
+        .current_dir("tests/fixture/cargo/")
+        .assert()
+        .success();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "movedValue"])
+        .current_dir("tests/fixture/cargo/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("movedValue.rs:4:27"));
+}
+
+#[test]
+fn hint_doesnt_generate_the_workspace() {
+    let dir = temp_dir("hint_workspace");
+    for file in ["info.toml", "compSuccess.rs", "testSuccess.rs", "movedValue.rs"] {
+        std::fs::copy(Path::new("tests/fixture/cargo").join(file), dir.join(file)).unwrap();
+    }
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["hint", "compSuccess"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    assert!(!dir.join(".rustlings/workspace").exists());
+}
+
 #[test]
 fn run_single_test_failure() {
     Command::cargo_bin("rustlings")
@@ -90,6 +150,39 @@ fn run_single_test_not_passed() {
         .code(1);
 }
 
+#[test]
+fn run_single_exercise_timeout() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "timeoutExercise"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("timed out after 1 s"));
+}
+
+#[test]
+fn run_single_exercise_output_limit() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "loudExercise"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("exceeded the output limit"));
+}
+
+#[test]
+fn run_build_script_exercise_timeout() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["run", "loopingTest"])
+        .current_dir("tests/fixture/failure/")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("timed out after 1 s"));
+}
+
 #[test]
 fn run_single_test_no_filename() {
     Command::cargo_bin("rustlings")
@@ -126,9 +219,332 @@ fn reset_no_exercise() {
         .arg("reset")
         .assert()
         .code(1)
-        .stderr(predicates::str::contains(
-            "positional arguments not provided",
+        .stdout(predicates::str::contains(
+            "Reset either an exercise by its name, a --category or --all of them",
+        ));
+}
+
+#[test]
+fn reset_restores_original_and_backs_up() {
+    let dir = temp_dir("reset");
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"pending_exercise\"\npath = \"pending_exercise.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::copy(
+        "tests/fixture/state/pending_exercise.rs",
+        dir.join("pending_exercise.rs"),
+    )
+    .unwrap();
+    let original = std::fs::read_to_string(dir.join("pending_exercise.rs")).unwrap();
+    let rustlings = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("rustlings").unwrap();
+        cmd.args(args).current_dir(&dir);
+        cmd
+    };
+
+    // The original exercise, as `rustlings init` keeps it
+    std::fs::create_dir_all(dir.join(".rustlings/pristine")).unwrap();
+    std::fs::write(dir.join(".rustlings/pristine/pending_exercise.rs"), &original).unwrap();
+    std::fs::write(dir.join("pending_exercise.rs"), "// my work\n").unwrap();
+    rustlings(&["reset", "pending_exercise"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("your version is backed up"));
+    assert_eq!(std::fs::read_to_string(dir.join("pending_exercise.rs")).unwrap(), original);
+
+    rustlings(&["restore-backup", "pending_exercise"]).assert().success();
+    assert_eq!(
+        std::fs::read_to_string(dir.join("pending_exercise.rs")).unwrap(),
+        "// my work\n"
+    );
+    rustlings(&["reset", "--all"]).assert().success();
+    assert_eq!(std::fs::read_to_string(dir.join("pending_exercise.rs")).unwrap(), original);
+}
+
+#[test]
+fn init_creates_exercises() {
+    let parent = temp_dir("init");
+    let dir = parent.join("rustlings");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&dir)
+        .current_dir(&parent)
+        .assert()
+        .success();
+    assert!(dir.join("exercises/intro/README.md").exists());
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names"])
+        .current_dir(&dir)
+        .assert()
+        .success();
+    // The exercises are never overwritten
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("init")
+        .arg(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("already exists"));
+}
+
+#[test]
+fn update_merges_changes_and_renames() {
+    let dir = temp_dir("update");
+    let new = temp_dir("update_new");
+    for dir in [&dir, &new] {
+        std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    }
+    let original = "// exercise\n\n// I AM NOT DONE\n\nfn main() {\n}\n";
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"old_name\"\npath = \"exercises/old_name.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("exercises/old_name.rs"), original).unwrap();
+    std::fs::write(
+        new.join("info.toml"),
+        "[[exercises]]\nname = \"new_name\"\npath = \"exercises/new_name.rs\"\naliases = [\"old_name\"]\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        new.join("exercises/new_name.rs"),
+        "// exercise, updated\n\n// I AM NOT DONE\n\nfn main() {\n}\n",
+    )
+    .unwrap();
+    std::fs::write(new.join("exercises/README.md"), "# Exercises\n").unwrap();
+    let rustlings = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("rustlings").unwrap();
+        cmd.args(args).current_dir(&dir);
+        cmd
+    };
+
+    // The original exercise to merge with, as `rustlings init` keeps it
+    std::fs::create_dir_all(dir.join(".rustlings/pristine/exercises")).unwrap();
+    std::fs::write(dir.join(".rustlings/pristine/exercises/old_name.rs"), original).unwrap();
+    std::fs::write(dir.join("exercises/old_name.rs"), "// exercise\n\nfn main() {\n}\n").unwrap();
+    std::fs::write(dir.join(".rustlings-state.json"), "{\"done\":{\"old_name\":\"hash\"}}").unwrap();
+    rustlings(&["update", "--from", new.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Merged the update"));
+    assert!(!dir.join("exercises/old_name.rs").exists());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("exercises/new_name.rs")).unwrap(),
+        "// exercise, updated\n\nfn main() {\n}\n"
+    );
+    assert!(dir.join("exercises/README.md").exists());
+    assert!(std::fs::read_to_string(dir.join(".rustlings-state.json"))
+        .unwrap()
+        .contains("new_name"));
+    rustlings(&["list", "--names"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("new_name"));
+}
+
+#[test]
+fn run_ignores_the_cache() {
+    let dir = temp_dir("run_cache");
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(
+        dir.join("info.toml"),
+        "[[exercises]]\nname = \"note\"\npath = \"exercises/note.rs\"\nmode = \"compile\"\nhint = \"\"\n",
+    )
+    .unwrap();
+    // What the exercise prints isn't part of what the cache depends on
+    std::fs::write(
+        dir.join("exercises/note.rs"),
+        "fn main() {\n    print!(\"{}\", std::fs::read_to_string(\"note.txt\").unwrap());\n}\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("note.txt"), "cached note").unwrap();
+    let rustlings = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("rustlings").unwrap();
+        cmd.args(args).current_dir(&dir);
+        cmd
+    };
+    rustlings(&["verify"]).assert().success();
+    std::fs::write(dir.join("note.txt"), "fresh note").unwrap();
+    rustlings(&["run", "note"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("fresh note"));
+}
+
+#[test]
+fn update_backs_up_replaced_exercises() {
+    let dir = temp_dir("update_replace");
+    let new = temp_dir("update_replace_new");
+    let info = "[[exercises]]\nname = \"exercise\"\npath = \"exercises/exercise.rs\"\nmode = \"compile\"\nhint = \"\"\n";
+    let original = "// exercise\n\n// I AM NOT DONE\n\nfn main() {\n}\n";
+    for (dir, contents) in [(&dir, original), (&new, "// exercise, updated\n\n// I AM NOT DONE\n\nfn main() {\n}\n")] {
+        std::fs::create_dir_all(dir.join("exercises")).unwrap();
+        std::fs::write(dir.join("info.toml"), info).unwrap();
+        std::fs::write(dir.join("exercises/exercise.rs"), contents).unwrap();
+    }
+    std::fs::create_dir_all(dir.join(".rustlings/pristine/exercises")).unwrap();
+    std::fs::write(dir.join(".rustlings/pristine/exercises/exercise.rs"), original).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["update", "--from", new.to_str().unwrap()])
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Updated exercises/exercise.rs, the previous version is backed up in"));
+    let backups: Vec<PathBuf> = std::fs::read_dir(dir.join(".rustlings/backups/exercise"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(backups.len(), 1);
+    assert_eq!(std::fs::read_to_string(&backups[0]).unwrap(), original);
+}
+
+#[test]
+fn update_leaves_a_solved_clone_alone() {
+    let dir = temp_dir("update_clone");
+    std::fs::create_dir_all(dir.join("exercises/intro")).unwrap();
+    let info = "[[exercises]]\nname = \"intro2\"\npath = \"exercises/intro/intro2.rs\"\nmode = \"compile\"\nhint = \"\"\n";
+    std::fs::write(dir.join("info.toml"), info).unwrap();
+    // A solved exercise in a clone, which never kept its original
+    let solved = std::fs::read_to_string("curriculum/exercises/intro/intro2.rs")
+        .unwrap()
+        .replace("// I AM NOT DONE\n\n", "")
+        .replace("Hello {}!", "Hello world!");
+    std::fs::write(dir.join("exercises/intro/intro2.rs"), &solved).unwrap();
+    let update = |args: &[&str]| {
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .arg("update")
+            .args(args)
+            .current_dir(&dir)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Updated intro2").not())
+    };
+    update(&[]);
+    assert_eq!(std::fs::read_to_string(dir.join("exercises/intro/intro2.rs")).unwrap(), solved);
+
+    // An update of the exercise is merged with the solution
+    let new = temp_dir("update_clone_new");
+    std::fs::create_dir_all(new.join("exercises/intro")).unwrap();
+    std::fs::write(new.join("info.toml"), info).unwrap();
+    let updated = std::fs::read_to_string("curriculum/exercises/intro/intro2.rs")
+        .unwrap()
+        .replace("a greeting to the world", "a greeting to the whole world");
+    std::fs::write(new.join("exercises/intro/intro2.rs"), updated).unwrap();
+    update(&["--from", new.to_str().unwrap()]).stdout(predicates::str::contains("Merged the update"));
+    let merged = std::fs::read_to_string(dir.join("exercises/intro/intro2.rs")).unwrap();
+    assert_eq!(merged, solved.replace("a greeting to the world", "a greeting to the whole world"));
+}
+
+#[test]
+fn check_info_reports_problems() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-info")
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+
+    let dir = temp_dir("check_info");
+    std::fs::create_dir_all(dir.join("exercises")).unwrap();
+    std::fs::write(dir.join("exercises/unlisted.rs"), "fn main() {}\n").unwrap();
+    let exercise = "[[exercises]]\nname = \"missing\"\npath = \"exercises/missing.rs\"\nmode = \"compile\"\nhint = \"\"\n";
+    std::fs::write(dir.join("info.toml"), exercise).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-info")
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "info.toml:3:8: error: the file `exercises/missing.rs` of the exercise `missing` doesn't exist",
+        ))
+        .stdout(predicates::str::contains(
+            "exercises/unlisted.rs: warning: no exercise in info.toml or a track uses this file",
+        ));
+    // Other commands refuse to work with an invalid info.toml
+    std::fs::write(dir.join("info.toml"), exercise.replace("compile", "tests")).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .current_dir(&dir)
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("info.toml:4:8: error: unknown variant `tests`"));
+    // Unknown keys are only warned about
+    std::fs::write(dir.join("exercises/missing.rs"), "fn main() {}\n").unwrap();
+    std::fs::write(dir.join("info.toml"), format!("{exercise}tmeout = 3\n")).unwrap();
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-info")
+        .current_dir(&dir)
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "info.toml:6:1: warning: rustlings doesn't know the key `tmeout` of the exercise `missing`, so it's ignored",
         ));
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("list")
+        .current_dir(&dir)
+        .assert()
+        .success();
+}
+
+#[test]
+fn tracks_have_their_own_exercises_and_progress() {
+    let dir = temp_dir("tracks");
+    std::fs::create_dir_all(dir.join("tracks")).unwrap();
+    for file in ["compSuccess.rs", "testSuccess.rs"] {
+        std::fs::copy(Path::new("tests/fixture/success").join(file), dir.join(file)).unwrap();
+    }
+    std::fs::copy("tests/fixture/state/pending_exercise.rs", dir.join("pending_exercise.rs")).unwrap();
+    let exercise = |name: &str, mode: &str| {
+        format!("[[exercises]]\nname = \"{name}\"\npath = \"{name}.rs\"\nmode = \"{mode}\"\nhint = \"\"\n")
+    };
+    std::fs::write(dir.join("info.toml"), exercise("pending_exercise", "compile")).unwrap();
+    std::fs::write(dir.join("tracks/basics.toml"), exercise("compSuccess", "compile")).unwrap();
+    std::fs::write(
+        dir.join("tracks/advanced.toml"),
+        format!("include = [\"basics\"]\n\n{}", exercise("testSuccess", "test")),
+    )
+    .unwrap();
+    let rustlings = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("rustlings").unwrap();
+        cmd.args(args).current_dir(&dir);
+        cmd
+    };
+
+    rustlings(&["--track", "advanced", "list", "--names"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("compSuccess\ntestSuccess\n"));
+    rustlings(&["--track", "basics", "run", "compSuccess"]).assert().success();
+    rustlings(&["--track", "basics", "list"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("You completed 1 / 1 exercises"));
+    // The progress on one track doesn't count for another
+    rustlings(&["--track", "advanced", "list"])
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("You completed 0 / 2 exercises"));
+    rustlings(&["list", "--names"])
+        .assert()
+        .success()
+        .stdout(predicates::str::starts_with("pending_exercise\n"));
+    rustlings(&["--track", "missing", "list"])
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains(
+            "There's no track named missing, the tracks are: advanced, basics",
+        ));
+    rustlings(&["check-info"]).assert().success();
 }
 
 #[test]
@@ -243,6 +659,17 @@ fn run_rustlings_list_both_done_and_pending() {
         .stdout(predicates::str::contains("Done").and(predicates::str::contains("Pending")));
 }
 
+#[test]
+fn run_rustlings_list_unverified_is_pending() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["list", "--names", "--unsolved"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("unverified_exercise"));
+}
+
 #[test]
 fn run_rustlings_list_without_pending() {
     Command::cargo_bin("rustlings")
@@ -264,3 +691,162 @@ fn run_rustlings_list_without_done() {
         .success()
         .stdout(predicates::str::contains("Done").not());
 }
+
+#[test]
+fn cicvverify_writes_results_to_output() {
+    // The directory of the output doesn't exist yet
+    let output = temp_dir("cicvverify_json").join("results/check_result.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("cicvverify")
+        .arg("--output")
+        .arg(&output)
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success();
+    let results = std::fs::read_to_string(&output).unwrap();
+    assert!(results.contains("\"total_succeeds\": 2"));
+}
+
+#[test]
+fn cicvverify_writes_results_in_format() {
+    for (format, expected) in [
+        ("junit", "<testsuite name=\"failure\""),
+        ("tap", "not ok 1 - compFailure # compile error"),
+        ("markdown", "### compFailure (compile error)"),
+    ] {
+        let output = temp_dir(&format!("cicvverify_{format}")).join("results");
+        Command::cargo_bin("rustlings")
+            .unwrap()
+            .args(["cicvverify", "--format", format, "--output"])
+            .arg(&output)
+            .current_dir("tests/fixture/failure")
+            .assert()
+            .success();
+        let results = std::fs::read_to_string(&output).unwrap();
+        assert!(results.contains(expected), "{results}");
+    }
+}
+
+#[test]
+fn cicvverify_counts_compiler_errors_without_colors() {
+    let output = temp_dir("cicvverify_errors").join("check_result.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--output"])
+        .arg(&output)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .success();
+    let results = std::fs::read_to_string(&output).unwrap();
+    assert!(results.contains("\"errors\": 1"), "{results}");
+    assert!(results.contains("error[E0382]: borrow of moved value: `vec0`"), "{results}");
+    assert!(!results.contains("\\u001b"), "{results}");
+}
+
+#[test]
+fn cicvverify_rejects_unknown_format() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["cicvverify", "--format", "yaml"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .code(1)
+        .stderr(predicates::str::contains("unknown format 'yaml'"));
+}
+
+#[test]
+fn verify_writes_junit_results() {
+    // The directory of the output doesn't exist yet
+    let output = temp_dir("verify_junit").join("results/results.xml");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["verify", "--junit"])
+        .arg(&output)
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1);
+    let results = std::fs::read_to_string(&output).unwrap();
+    assert!(results.contains(r#"<failure type="CompileError""#), "{results}");
+    assert!(results.contains("<skipped />"), "{results}");
+}
+
+#[test]
+fn hidden_tests_only_run_when_grading() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("verify")
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .success();
+
+    // The directory of the output doesn't exist yet
+    let output = temp_dir("cicvverify_hidden").join("results/check_result.json");
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("cicvverify")
+        .arg("--output")
+        .arg(&output)
+        .current_dir("tests/fixture/hidden")
+        .assert()
+        .success();
+    let results = std::fs::read_to_string(&output).unwrap();
+    assert!(results.contains("\"failure\": \"hidden_test_failure\""), "{results}");
+}
+
+#[test]
+fn solution_shows_reference_solution() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "compSuccess"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Hello from the solution!"));
+}
+
+#[test]
+fn solution_diffs_against_exercise() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "compSuccess", "--diff"])
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains(
+            "+     println!(\"Hello from the solution!\");",
+        ));
+}
+
+#[test]
+fn solution_of_pending_exercise_is_hidden() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .args(["solution", "pending_exercise"])
+        .current_dir("tests/fixture/state")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("Solve pending_exercise first"));
+}
+
+#[test]
+fn check_solutions_grades_solutions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-solutions")
+        .current_dir("tests/fixture/success")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("All 2 solutions passed"));
+}
+
+#[test]
+fn check_solutions_fails_on_missing_solutions() {
+    Command::cargo_bin("rustlings")
+        .unwrap()
+        .arg("check-solutions")
+        .current_dir("tests/fixture/failure")
+        .assert()
+        .code(1)
+        .stdout(predicates::str::contains("compFailure has no solution"));
+}